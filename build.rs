@@ -1,8 +1,59 @@
 extern crate embed_resource;
 
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
 fn main() {
     let target = std::env::var("TARGET").unwrap();
     if target.contains("windows") {
         embed_resource::compile("assets/icon.rc");
     }
+    if env::var_os("CARGO_FEATURE_EMBEDDED_ASSETS").is_some() {
+        embed_assets();
+    }
+}
+
+// Writes `EMBEDDED_ASSETS`, every file in `assets/` keyed by its path relative to `assets/`.
+fn embed_assets() {
+    println!("cargo:rerun-if-changed=assets");
+    let root = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("assets");
+    let mut files = Vec::new();
+    collect_files(&root, &mut files);
+    files.sort();
+
+    let mut table = String::from("pub static EMBEDDED_ASSETS: &[(&str, &[u8])] = &[\n");
+    for file in files {
+        let key = file
+            .strip_prefix(&root)
+            .unwrap()
+            .components()
+            .map(|c| c.as_os_str().to_str().unwrap())
+            .collect::<Vec<_>>()
+            .join("/");
+        println!("cargo:rerun-if-changed={}", file.display());
+        table.push_str(&format!(
+            "    ({:?}, include_bytes!({:?})),\n",
+            key,
+            file.to_str().unwrap()
+        ));
+    }
+    table.push_str("];\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("embedded_assets.rs");
+    fs::write(out, table).unwrap();
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy();
+        if path.is_dir() {
+            collect_files(&path, files);
+        // Editor backups are not used by the game.
+        } else if !name.contains(".backup.") {
+            files.push(path);
+        }
+    }
 }
@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What the player can do. [`FrameInput`](crate::FrameInput) reads them from their
+/// [`Bindings`] every frame, gameplay systems only see the actions.
+///
+/// Aiming is not bound, it follows the mouse or a gamepad's right stick, see
+/// [`Aim`](crate::Aim).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// Jump toward the aim, also clicks menu items.
+    Jump,
+    Confirm,
+    Pause,
+    /// Retry from the main round after the game ended.
+    Retry,
+    Up,
+    Down,
+}
+
+/// One binding per device for an action, `None` leaves the device unbound.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionBinding {
+    pub key: Option<KeyCode>,
+    pub mouse: Option<MouseButton>,
+    pub gamepad: Option<GamepadButtonType>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bindings {
+    pub jump: ActionBinding,
+    pub confirm: ActionBinding,
+    pub pause: ActionBinding,
+    pub retry: ActionBinding,
+    pub up: ActionBinding,
+    pub down: ActionBinding,
+}
+
+/// The state of every device an action can be bound to.
+pub struct Devices<'a> {
+    pub keys: &'a Input<KeyCode>,
+    pub mouse: &'a Input<MouseButton>,
+    pub gamepad_buttons: &'a Input<GamepadButton>,
+}
+
+impl Action {
+    pub const ALL: [Action; 6] = [
+        Action::Jump,
+        Action::Confirm,
+        Action::Pause,
+        Action::Retry,
+        Action::Up,
+        Action::Down,
+    ];
+}
+
+impl ActionBinding {
+    fn new(
+        key: Option<KeyCode>,
+        mouse: Option<MouseButton>,
+        gamepad: Option<GamepadButtonType>,
+    ) -> Self {
+        ActionBinding {
+            key,
+            mouse,
+            gamepad,
+        }
+    }
+
+    pub fn just_pressed(&self, devices: &Devices) -> bool {
+        self.key.map_or(false, |key| devices.keys.just_pressed(key))
+            || self
+                .mouse
+                .map_or(false, |button| devices.mouse.just_pressed(button))
+            || self.gamepad.map_or(false, |button_type| {
+                devices
+                    .gamepad_buttons
+                    .get_just_pressed()
+                    .any(|button| button.1 == button_type)
+            })
+    }
+
+    pub fn pressed(&self, devices: &Devices) -> bool {
+        self.key.map_or(false, |key| devices.keys.pressed(key))
+            || self
+                .mouse
+                .map_or(false, |button| devices.mouse.pressed(button))
+            || self.gamepad.map_or(false, |button_type| {
+                devices
+                    .gamepad_buttons
+                    .get_pressed()
+                    .any(|button| button.1 == button_type)
+            })
+    }
+
+    /// The key or button just pressed, bound on its device only.
+    pub fn from_press(devices: &Devices) -> Option<Self> {
+        if let Some(key) = devices.keys.get_just_pressed().next() {
+            Some(ActionBinding::new(Some(*key), None, None))
+        } else if let Some(button) = devices.mouse.get_just_pressed().next() {
+            Some(ActionBinding::new(None, Some(*button), None))
+        } else {
+            devices
+                .gamepad_buttons
+                .get_just_pressed()
+                .next()
+                .map(|button| ActionBinding::new(None, None, Some(button.1)))
+        }
+    }
+
+    /// Replaces the binding of every device `other` is bound on.
+    pub fn replace(&mut self, other: &ActionBinding) {
+        self.key = other.key.or(self.key);
+        self.mouse = other.mouse.or(self.mouse);
+        self.gamepad = other.gamepad.or(self.gamepad);
+    }
+
+    /// Both are bound to the same key or button.
+    pub fn overlaps(&self, other: &ActionBinding) -> bool {
+        (self.key.is_some() && self.key == other.key)
+            || (self.mouse.is_some() && self.mouse == other.mouse)
+            || (self.gamepad.is_some() && self.gamepad == other.gamepad)
+    }
+
+    /// E.g. `Space / Left / RightTrigger2`.
+    pub fn describe(&self) -> String {
+        let parts: Vec<_> = [
+            self.key.map(|key| format!("{:?}", key)),
+            self.mouse.map(|button| format!("{:?}", button)),
+            self.gamepad.map(|button_type| format!("{:?}", button_type)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if parts.is_empty() {
+            String::from("none")
+        } else {
+            parts.join(" / ")
+        }
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use GamepadButtonType as Pad;
+        Bindings {
+            jump: ActionBinding::new(
+                Some(KeyCode::Space),
+                Some(MouseButton::Left),
+                Some(Pad::RightTrigger2),
+            ),
+            confirm: ActionBinding::new(Some(KeyCode::Return), None, Some(Pad::South)),
+            pause: ActionBinding::new(Some(KeyCode::Escape), None, Some(Pad::Start)),
+            retry: ActionBinding::new(Some(KeyCode::R), None, Some(Pad::North)),
+            up: ActionBinding::new(Some(KeyCode::Up), None, Some(Pad::DPadUp)),
+            down: ActionBinding::new(Some(KeyCode::Down), None, Some(Pad::DPadDown)),
+        }
+    }
+}
+
+impl Bindings {
+    pub fn get(&self, action: Action) -> &ActionBinding {
+        match action {
+            Action::Jump => &self.jump,
+            Action::Confirm => &self.confirm,
+            Action::Pause => &self.pause,
+            Action::Retry => &self.retry,
+            Action::Up => &self.up,
+            Action::Down => &self.down,
+        }
+    }
+
+    pub fn get_mut(&mut self, action: Action) -> &mut ActionBinding {
+        match action {
+            Action::Jump => &mut self.jump,
+            Action::Confirm => &mut self.confirm,
+            Action::Pause => &mut self.pause,
+            Action::Retry => &mut self.retry,
+            Action::Up => &mut self.up,
+            Action::Down => &mut self.down,
+        }
+    }
+
+    pub fn just_pressed(&self, action: Action, devices: &Devices) -> bool {
+        self.get(action).just_pressed(devices)
+    }
+
+    /// Held down on any of the bound devices.
+    pub fn pressed(&self, action: Action, devices: &Devices) -> bool {
+        self.get(action).pressed(devices)
+    }
+
+    /// The other action `binding` is already bound to, if any.
+    pub fn taken(&self, action: Action, binding: &ActionBinding) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|other| *other != action && self.get(*other).overlaps(binding))
+    }
+
+    pub fn is_key_bound(&self, key: KeyCode) -> bool {
+        Action::ALL
+            .iter()
+            .any(|action| self.get(*action).key == Some(key))
+    }
+}
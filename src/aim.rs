@@ -0,0 +1,137 @@
+use crate::{
+    settings::GamepadAim, util::screen_to_world_pos, MainCamera, Player, Settings, Tuning,
+    HALF_HEIGHT, HALF_WIDTH,
+};
+use bevy::{
+    input::{gamepad::GamepadEventType, InputSystem},
+    math::Vec3Swizzles,
+    prelude::*,
+    utils::HashSet,
+};
+
+/// Aiming with the mouse or a gamepad's right stick.
+pub struct AimPlugin;
+
+impl Plugin for AimPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Aim>()
+            .init_resource::<GamepadLobby>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                GamepadLobby::update
+                    .label("GamepadLobby::update")
+                    .after(InputSystem),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                Aim::update
+                    .label("Aim::update")
+                    .after("GamepadLobby::update"),
+            );
+    }
+}
+
+/// Where the player aims, shown by the [`MouseCursor`](crate::MouseCursor). A gamepad jumps
+/// toward it with a trigger.
+#[derive(Default)]
+pub struct Aim {
+    /// World position.
+    pub position: Vec2,
+    /// A gamepad moved the aim last, the mouse takes over again once it moves.
+    pub gamepad: bool,
+    /// The last right stick position, for [`GamepadAim::Direction`].
+    direction: Vec2,
+}
+
+/// The connected gamepads.
+#[derive(Default)]
+pub struct GamepadLobby(pub HashSet<Gamepad>);
+
+impl GamepadLobby {
+    pub fn update(mut lobby: ResMut<GamepadLobby>, mut ev_gamepad: EventReader<GamepadEvent>) {
+        for GamepadEvent(gamepad, event_type) in ev_gamepad.iter() {
+            match event_type {
+                GamepadEventType::Connected => {
+                    info!("Gamepad {:?} connected", gamepad);
+                    lobby.0.insert(*gamepad);
+                }
+                GamepadEventType::Disconnected => {
+                    info!("Gamepad {:?} disconnected", gamepad);
+                    lobby.0.remove(gamepad);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Aim {
+    /// Stick positions closer to the center are ignored.
+    const DEAD_ZONE: f32 = 0.2;
+
+    pub fn update(
+        mut aim: ResMut<Aim>,
+        lobby: Res<GamepadLobby>,
+        axes: Res<Axis<GamepadAxis>>,
+        mut ev_cursor_moved: EventReader<CursorMoved>,
+        q_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+        q_player: Query<&Transform, With<Player>>,
+        windows: Res<Windows>,
+        settings: Res<Settings>,
+        tuning: Option<Res<Tuning>>,
+        time: Res<Time>,
+    ) {
+        if ev_cursor_moved.iter().next().is_some() {
+            aim.gamepad = false;
+        }
+
+        let stick = lobby
+            .0
+            .iter()
+            .map(|gamepad| {
+                Vec2::new(
+                    axes.get(GamepadAxis(*gamepad, GamepadAxisType::RightStickX))
+                        .unwrap_or(0.),
+                    axes.get(GamepadAxis(*gamepad, GamepadAxisType::RightStickY))
+                        .unwrap_or(0.),
+                )
+            })
+            .find(|stick| stick.length() > Self::DEAD_ZONE);
+        if let Some(stick) = stick {
+            aim.gamepad = true;
+            aim.direction = stick;
+        }
+
+        if aim.gamepad {
+            // Loaded with the other assets.
+            let tuning = match tuning {
+                Some(tuning) => tuning,
+                None => return,
+            };
+            match settings.gamepad_aim {
+                GamepadAim::Cursor => {
+                    if let Some(stick) = stick {
+                        aim.position += stick * tuning.gamepad.cursor_speed * time.delta_seconds();
+                    }
+                }
+                GamepadAim::Direction => {
+                    let player = q_player
+                        .get_single()
+                        .map_or(Vec2::ZERO, |transform| transform.translation.xy());
+                    aim.position = player + aim.direction * tuning.gamepad.reach;
+                }
+            }
+            aim.position = aim.position.clamp(
+                Vec2::new(-HALF_WIDTH, -HALF_HEIGHT),
+                Vec2::new(HALF_WIDTH, HALF_HEIGHT),
+            );
+        } else if let Some((camera, camera_transform)) = q_camera.iter().next() {
+            // There is no window when running headless.
+            if let Some(window) = windows.get(camera.window) {
+                if let Some(world_pos) = screen_to_world_pos(camera, camera_transform, window) {
+                    aim.position = world_pos;
+                }
+            }
+        }
+    }
+}
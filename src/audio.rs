@@ -0,0 +1,181 @@
+use crate::{game, music::Track, AppState, Settings};
+use bevy::{prelude::*, utils::HashMap};
+use bevy_kira_audio::{Audio, AudioChannel, AudioSource};
+
+/// Plays the [`PlaySound`] events, with the volumes from the [`Settings`]. The music is played by
+/// [`MusicPlugin`](crate::music::MusicPlugin).
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(bevy_kira_audio::AudioPlugin)
+            .init_resource::<SoundChannels>()
+            .add_event::<PlaySound>()
+            .add_system_set(
+                SystemSet::on_update(AppState::Ready)
+                    .label("ready_update")
+                    .with_system(Sounds::on_phase.after("Game::update")),
+            )
+            .add_system(SoundChannels::play);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sound {
+    /// A slime ball hitting a wall or a body part.
+    Bounce,
+    /// A slime ball hitting the player.
+    Hit,
+    Ouch,
+    Split,
+    Shoot,
+    Jump,
+    /// The intro starts.
+    Start,
+    /// The intro's slime balls are gone.
+    Dead,
+    /// The main round starts.
+    Main,
+    End,
+    Egg,
+}
+
+/// Sent to play a sound effect.
+pub struct PlaySound {
+    pub sound: Sound,
+    /// From 0 to 1, scaled by the effects and master volume.
+    pub volume: f32,
+}
+
+/// The sound effects and music tracks, loaded with the other assets.
+pub struct Sounds {
+    pub effects: HashMap<Sound, Handle<AudioSource>>,
+    pub tracks: HashMap<Track, Handle<AudioSource>>,
+}
+
+/// `bevy_kira_audio` only sets volumes per channel, so every effect plays in the next of a few
+/// channels at its own volume.
+pub struct SoundChannels {
+    sfx: Vec<AudioChannel>,
+    next: usize,
+}
+
+impl Sound {
+    pub const ALL: [Sound; 11] = [
+        Sound::Bounce,
+        Sound::Hit,
+        Sound::Ouch,
+        Sound::Split,
+        Sound::Shoot,
+        Sound::Jump,
+        Sound::Start,
+        Sound::Dead,
+        Sound::Main,
+        Sound::End,
+        Sound::Egg,
+    ];
+
+    pub fn path(self) -> &'static str {
+        match self {
+            Sound::Bounce => "sounds/bounce.wav",
+            Sound::Hit => "sounds/hit.wav",
+            Sound::Ouch => "sounds/ouch.wav",
+            Sound::Split => "sounds/split.wav",
+            Sound::Shoot => "sounds/shoot.wav",
+            Sound::Jump => "sounds/jump.wav",
+            Sound::Start => "sounds/start.wav",
+            Sound::Dead => "sounds/dead.wav",
+            Sound::Main => "sounds/main.wav",
+            Sound::End => "sounds/end.wav",
+            Sound::Egg => "sounds/egg.wav",
+        }
+    }
+}
+
+impl PlaySound {
+    pub fn new(sound: Sound) -> Self {
+        PlaySound { sound, volume: 1. }
+    }
+}
+
+impl Sounds {
+    pub fn load(mut commands: Commands, asset_server: Res<AssetServer>) {
+        commands.insert_resource(Sounds {
+            effects: Sound::ALL
+                .iter()
+                .map(|sound| (*sound, asset_server.load(sound.path())))
+                .collect(),
+            tracks: Track::ALL
+                .iter()
+                .map(|track| (*track, asset_server.load(track.path())))
+                .collect(),
+        });
+    }
+
+    /// Every handle with the path it was loaded from.
+    pub fn handles(&self) -> impl Iterator<Item = (&'static str, &Handle<AudioSource>)> {
+        self.effects
+            .iter()
+            .map(|(sound, handle)| (sound.path(), handle))
+            .chain(
+                self.tracks
+                    .iter()
+                    .map(|(track, handle)| (track.path(), handle)),
+            )
+    }
+
+    /// Plays a jingle when the game enters a new phase.
+    pub fn on_phase(
+        mut ev_phase: EventReader<game::Phase>,
+        mut ev_play_sound: EventWriter<PlaySound>,
+    ) {
+        for ev in ev_phase.iter() {
+            let sound = match ev {
+                game::Phase::TransIntro => Sound::Start,
+                game::Phase::TransDead => Sound::Dead,
+                game::Phase::TransMain => Sound::Main,
+                game::Phase::TransEnd => Sound::End,
+                game::Phase::TransEgg => Sound::Egg,
+                _ => continue,
+            };
+            ev_play_sound.send(PlaySound::new(sound));
+        }
+    }
+}
+
+impl Default for SoundChannels {
+    fn default() -> Self {
+        SoundChannels {
+            sfx: (0..8)
+                .map(|i| AudioChannel::new(format!("sfx_{}", i)))
+                .collect(),
+            next: 0,
+        }
+    }
+}
+
+impl SoundChannels {
+    pub fn play(
+        mut ev_play_sound: EventReader<PlaySound>,
+        mut channels: ResMut<SoundChannels>,
+        sounds: Option<Res<Sounds>>,
+        audio: Res<Audio>,
+        settings: Res<Settings>,
+    ) {
+        let sounds = match sounds {
+            Some(sounds) => sounds,
+            None => return,
+        };
+        for ev in ev_play_sound.iter() {
+            let handle = match sounds.effects.get(&ev.sound) {
+                Some(handle) => handle.clone(),
+                None => continue,
+            };
+            let channel = &channels.sfx[channels.next];
+            let volume = ev.volume.clamp(0., 1.) * settings.sfx_volume * settings.master_volume;
+            audio.set_volume_in_channel(volume, channel);
+            audio.play_in_channel(handle, channel);
+            channels.next = (channels.next + 1) % channels.sfx.len();
+        }
+    }
+}
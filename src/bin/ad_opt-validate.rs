@@ -0,0 +1,199 @@
+//! Loads every sprite sheet in `assets/sprites.manifest.ron` without a window and checks that it
+//! has the frames and animation tags the game reads. Exits with status 1 if anything is missing.
+//!
+//! Run with `cargo run --bin ad_opt-validate` from the repository root.
+
+use bevy::{
+    asset::{AssetPlugin, LoadState},
+    prelude::*,
+    utils::Duration,
+};
+use bevy_ase::{
+    asset::{Animation, AseFileMap},
+    loader::{self, Loader},
+};
+use bevy_jam_one::{
+    loading::LOADING_TIMEOUT_SECS, sprites::SpriteSheet, AssetProblem, SpriteManifest,
+    SpriteManifestPlugin,
+};
+use std::{path::Path, process, thread, time::Instant};
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        // Normally registered by the render and sprite plugins.
+        .add_asset::<Image>()
+        .add_asset::<TextureAtlas>()
+        .add_plugin(loader::AseLoaderDefaultPlugin)
+        .add_plugin(benimator::AnimationPlugin::default())
+        .add_plugin(SpriteManifestPlugin);
+
+    let manifest = match load_manifest(&mut app) {
+        Ok(manifest) => manifest,
+        Err(problem) => {
+            eprintln!("{}", problem);
+            process::exit(1);
+        }
+    };
+    let mut problems = load_sheets(&mut app, &manifest);
+
+    let ase_file_map = app.world.get_resource::<AseFileMap>().unwrap();
+    let animations = app.world.get_resource::<Assets<Animation>>().unwrap();
+    let images = app.world.get_resource::<Assets<Image>>().unwrap();
+    let texture_atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+    for sheet in &manifest.sheets {
+        report(
+            &manifest,
+            sheet,
+            ase_file_map,
+            animations,
+            images,
+            texture_atlases,
+        );
+    }
+    problems.extend(manifest.validate(ase_file_map, animations));
+
+    if problems.is_empty() {
+        println!("\nAll {} sprite sheets are valid", manifest.sheets.len());
+    } else {
+        eprintln!("\n{} problems:", problems.len());
+        for problem in &problems {
+            eprintln!("  {}", problem);
+        }
+        process::exit(1);
+    }
+}
+
+// Step the app until `done` returns something or loading timed out.
+fn update_until<T>(app: &mut App, mut done: impl FnMut(&mut App) -> Option<T>) -> Option<T> {
+    let start = Instant::now();
+    while start.elapsed().as_secs_f32() < LOADING_TIMEOUT_SECS {
+        app.update();
+        if let Some(result) = done(app) {
+            return Some(result);
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    None
+}
+
+fn load_manifest(app: &mut App) -> Result<SpriteManifest, AssetProblem> {
+    let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+    let handle: Handle<SpriteManifest> = asset_server.load(SpriteManifest::PATH);
+    let path = SpriteManifest::PATH.to_owned();
+
+    update_until(app, |app| {
+        let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+        if asset_server.get_load_state(&handle) == LoadState::Failed {
+            return Some(Err(AssetProblem::Invalid { path: path.clone() }));
+        }
+        let manifests = app.world.get_resource::<Assets<SpriteManifest>>().unwrap();
+        manifests.get(&handle).cloned().map(Ok)
+    })
+    .unwrap_or_else(|| Err(AssetProblem::TimedOut { path: path.clone() }))
+}
+
+// Returns the sheets that failed or did not finish loading in time.
+fn load_sheets(app: &mut App, manifest: &SpriteManifest) -> Vec<AssetProblem> {
+    let asset_server = app.world.get_resource::<AssetServer>().unwrap().clone();
+    let handles: Vec<_> = manifest
+        .sheets
+        .iter()
+        .map(|sheet| {
+            let handle = asset_server.load(Path::new(&sheet.path));
+            app.world
+                .get_resource_mut::<Loader>()
+                .unwrap()
+                .add(handle.clone());
+            (sheet.path.as_str(), handle)
+        })
+        .collect();
+
+    let mut problems = Vec::new();
+    let is_pending = |app: &App, path: &str| {
+        app.world
+            .get_resource::<AseFileMap>()
+            .unwrap()
+            .get(Path::new(path))
+            .is_none()
+    };
+    let is_failed = |handle: &Handle<_>| asset_server.get_load_state(handle) == LoadState::Failed;
+    // Keeps waiting for the other sheets after one failed, so they are all reported.
+    update_until(app, |app| {
+        let pending = handles
+            .iter()
+            .any(|(path, handle)| !is_failed(handle) && is_pending(app, path));
+        (!pending).then(|| ())
+    });
+
+    for (path, handle) in &handles {
+        if is_failed(handle) {
+            problems.push(AssetProblem::MissingFile {
+                path: path.to_string(),
+            });
+        } else if is_pending(app, path) {
+            problems.push(AssetProblem::TimedOut {
+                path: path.to_string(),
+            });
+        }
+    }
+    problems
+}
+
+// Print the size of every frame and the durations of every animation the game reads.
+fn report(
+    manifest: &SpriteManifest,
+    sheet: &SpriteSheet,
+    ase_file_map: &AseFileMap,
+    animations: &Assets<Animation>,
+    images: &Assets<Image>,
+    texture_atlases: &Assets<TextureAtlas>,
+) {
+    println!("{} ({})", sheet.name, sheet.path);
+    let loaded = match manifest.sheet(ase_file_map, &sheet.name) {
+        Ok(loaded) => loaded,
+        Err(_) => {
+            println!("  not loaded");
+            return;
+        }
+    };
+
+    for &frame in &sheet.frames {
+        match loaded.texture(frame).ok().and_then(|t| images.get(t)) {
+            Some(image) => {
+                let size = image.size();
+                println!("  frame {}: {}x{}", frame, size.x, size.y);
+            }
+            None => println!("  frame {}: missing", frame),
+        }
+    }
+
+    for tag in &sheet.tags {
+        let (texture_atlas, anim) = match loaded.animation(animations, tag) {
+            Ok(components) => components,
+            Err(_) => {
+                println!("  \"{}\": missing", tag);
+                continue;
+            }
+        };
+        let texture_atlas = texture_atlases.get(texture_atlas);
+        let frames: Vec<_> = anim
+            .frames
+            .iter()
+            .map(|frame| {
+                let size = texture_atlas
+                    .and_then(|atlas| atlas.textures.get(frame.index))
+                    .map(|rect| format!("{}x{}", rect.width(), rect.height()))
+                    .unwrap_or_else(|| String::from("?"));
+                format!("{} {}ms", size, frame.duration.as_millis())
+            })
+            .collect();
+        println!(
+            "  \"{}\": {} frames [{}]",
+            tag,
+            frames.len(),
+            frames.join(", ")
+        );
+    }
+}
@@ -1,9 +1,32 @@
 use crate::nalgebra::Vector2;
-use crate::{game, HALF_WIDTH, PHYSICS_SCALE};
+use crate::{game, util, AppState, AssetProblem, SpriteManifest, Tuning, HALF_WIDTH};
 use bevy::prelude::*;
 use bevy_ase::asset::AseFileMap;
 use bevy_rapier2d::prelude::*;
-use std::path::Path;
+
+pub struct BodyPartPlugin;
+
+impl Plugin for BodyPartPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::Restart).with_system(util::despawn_all::<BodyPart>),
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::Ready)
+                .label("ready_enter")
+                .with_system(BodyPart::setup.chain(AssetProblem::report)),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Ready)
+                .label("ready_update")
+                .with_system(
+                    BodyPart::win_check
+                        .label("BodyPart::win_check")
+                        .before("Game::update"),
+                ),
+        );
+    }
+}
 
 #[derive(Component)]
 pub struct BodyPart {}
@@ -11,38 +34,45 @@ pub struct BodyPart {}
 pub struct BodyPartTextures(pub [Handle<Image>; 6]);
 
 impl BodyPart {
-    pub fn setup(mut commands: Commands, ase_file_map: Res<AseFileMap>) {
+    pub fn setup(
+        mut commands: Commands,
+        sprite_manifest: Res<SpriteManifest>,
+        ase_file_map: Res<AseFileMap>,
+    ) -> Result<(), AssetProblem> {
         //commands.spawn().insert(Game);
-        let target_assets = ase_file_map
-            .get(Path::new("sprites/body_parts.aseprite"))
-            .unwrap();
+        let sheet = sprite_manifest.sheet(&ase_file_map, "body_parts")?;
 
         commands.insert_resource(BodyPartTextures([
-            target_assets.texture(0).unwrap().clone(),
-            target_assets.texture(1).unwrap().clone(),
-            target_assets.texture(2).unwrap().clone(),
-            target_assets.texture(3).unwrap().clone(),
-            target_assets.texture(4).unwrap().clone(),
-            target_assets.texture(5).unwrap().clone(),
+            sheet.texture(0)?,
+            sheet.texture(1)?,
+            sheet.texture(2)?,
+            sheet.texture(3)?,
+            sheet.texture(4)?,
+            sheet.texture(5)?,
         ]));
+        Ok(())
     }
 
     pub fn win_check(
         mut ev_phase: EventReader<game::Phase>,
         query_pipeline: Res<QueryPipeline>,
         collider_query: QueryPipelineColliderComponentsQuery,
-        q_body_part: Query<&RigidBodyVelocityComponent, With<BodyPart>>,
+        q_body_part: Query<
+            (&RigidBodyVelocityComponent, &RigidBodyPositionComponent),
+            With<BodyPart>,
+        >,
         mut ev_won: EventWriter<game::Won>,
+        tuning: Res<Tuning>,
     ) {
         for ev in ev_phase.iter() {
             if let game::Phase::TransMain = ev {
                 let collider_set = QueryPipelineColliderComponentsSet(&collider_query);
 
                 let ray = Ray::new(
-                    Point::new(-HALF_WIDTH / PHYSICS_SCALE, 0.),
+                    Point::new(-HALF_WIDTH / tuning.physics_scale, 0.),
                     Vector2::new(1.0, 0.0),
                 );
-                let max_toi = HALF_WIDTH * 2. / PHYSICS_SCALE;
+                let max_toi = HALF_WIDTH * 2. / tuning.physics_scale;
                 let solid = true;
                 let groups = InteractionGroups::new(1 << 5, 1 << 3);
                 let filter = None;
@@ -55,9 +85,17 @@ impl BodyPart {
                     groups,
                     filter,
                     |handle, _| {
-                        if let Ok(rigid_body_velocity) = q_body_part.get(handle.entity()) {
+                        if let Ok((rigid_body_velocity, rigid_body_position)) =
+                            q_body_part.get(handle.entity())
+                        {
                             if rigid_body_velocity.linvel.magnitude() < 0.1 {
-                                ev_won.send(game::Won);
+                                ev_won.send(game::Won {
+                                    position: rigid_body_position
+                                        .position
+                                        .translation
+                                        .vector
+                                        .into(),
+                                });
                                 return false;
                             }
                         }
@@ -0,0 +1,156 @@
+use crate::{game, letterbox::Letterbox, AppState, MainCamera, PlayerDamaged, Tuning};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+/// Screen shake and hit-stop when the player is hit, and a zoom toward the winning body part.
+/// Each effect is configured, and can be turned off, in the `camera` section of the tuning.
+pub struct CameraEffectsPlugin;
+
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraEffects>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::Ready)
+                    .label("ready_enter")
+                    .with_system(CameraEffects::reset),
+            )
+            // Also stops the zoom after going back to the title menu from the end.
+            .add_system_set(
+                SystemSet::on_enter(AppState::Menu)
+                    .label("menu_enter")
+                    .with_system(CameraEffects::reset),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Ready)
+                    .label("ready_update")
+                    .with_system(
+                        CameraEffects::on_player_damaged
+                            .label("CameraEffects::on_player_damaged")
+                            .after("SlimeBall::on_contact_started"),
+                    )
+                    .with_system(CameraEffects::hit_stop.after("CameraEffects::on_player_damaged"))
+                    .with_system(CameraEffects::on_won.after("BodyPart::win_check")),
+            )
+            // The shake settles and the zoom goes on after the game ended.
+            .add_system(CameraEffects::update.label("CameraEffects::update"));
+    }
+}
+
+#[derive(Default)]
+pub struct CameraEffects {
+    /// From 0 to 1, the shake grows with its square.
+    trauma: f32,
+    /// Frames left with the physics step stopped. Counted in frames, so replays stay in sync.
+    hit_stop_frames: u32,
+    /// The body part zoomed toward in pixels, and the seconds since.
+    zoom: Option<(Vec2, f32)>,
+}
+
+impl CameraEffects {
+    /// The physics step is stopped for a hit-stop. Resuming from the pause menu keeps it stopped.
+    pub fn hit_stopped(&self) -> bool {
+        self.hit_stop_frames > 0
+    }
+
+    pub fn reset(
+        mut effects: ResMut<CameraEffects>,
+        mut rapier_configuration: ResMut<RapierConfiguration>,
+    ) {
+        if effects.hit_stop_frames > 0 {
+            rapier_configuration.physics_pipeline_active = true;
+        }
+        *effects = CameraEffects::default();
+    }
+
+    pub fn on_player_damaged(
+        mut ev_player_damaged: EventReader<PlayerDamaged>,
+        mut effects: ResMut<CameraEffects>,
+        mut rapier_configuration: ResMut<RapierConfiguration>,
+        tuning: Res<Tuning>,
+    ) {
+        let camera = &tuning.camera;
+        for ev in ev_player_damaged.iter() {
+            let speed = ev.vel.magnitude();
+            if camera.shake.enabled {
+                effects.trauma = (effects.trauma + speed * camera.shake.trauma_per_speed).min(1.);
+            }
+            if camera.hit_stop.enabled && speed >= camera.hit_stop.min_speed {
+                effects.hit_stop_frames = camera.hit_stop.frames;
+                rapier_configuration.physics_pipeline_active = false;
+            }
+        }
+    }
+
+    pub fn hit_stop(
+        mut effects: ResMut<CameraEffects>,
+        mut rapier_configuration: ResMut<RapierConfiguration>,
+    ) {
+        if effects.hit_stop_frames > 0 {
+            effects.hit_stop_frames -= 1;
+            if effects.hit_stop_frames == 0 {
+                rapier_configuration.physics_pipeline_active = true;
+            }
+        }
+    }
+
+    pub fn on_won(
+        mut ev_won: EventReader<game::Won>,
+        mut effects: ResMut<CameraEffects>,
+        tuning: Res<Tuning>,
+    ) {
+        for ev in ev_won.iter() {
+            if tuning.camera.zoom.enabled {
+                effects.zoom = Some((ev.position * tuning.physics_scale, 0.));
+            }
+        }
+    }
+
+    pub fn update(
+        mut effects: ResMut<CameraEffects>,
+        mut q_camera: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+        letterbox: Res<Letterbox>,
+        time: Res<Time>,
+        tuning: Option<Res<Tuning>>,
+    ) {
+        let tuning = match tuning {
+            Some(tuning) => tuning,
+            None => return,
+        };
+        let (mut transform, mut projection) = match q_camera.get_single_mut() {
+            Ok(camera) => camera,
+            Err(_) => return,
+        };
+        let shake = &tuning.camera.shake;
+        let zoom = &tuning.camera.zoom;
+
+        effects.trauma = (effects.trauma - shake.decay * time.delta_seconds()).max(0.);
+        let (center, scale) = match &mut effects.zoom {
+            Some((target, elapsed)) => {
+                *elapsed += time.delta_seconds();
+                let t = (*elapsed / zoom.secs).min(1.);
+                // Eases in and out.
+                let t = t * t * (3. - 2. * t);
+                (*target * t, 1. + (zoom.scale - 1.) * t)
+            }
+            None => (Vec2::ZERO, 1.),
+        };
+
+        // Only for looks, so it does not draw from the `GameRng`.
+        let mut rng = rand::thread_rng();
+        let amount = effects.trauma * effects.trauma;
+        let offset = Vec2::new(rng.gen_range(-1f32..=1.), rng.gen_range(-1f32..=1.))
+            * shake.max_offset
+            * amount;
+        let angle = rng.gen_range(-1f32..=1.) * shake.max_angle * amount;
+
+        let translation = (center + offset).extend(transform.translation.z);
+        if transform.translation != translation {
+            transform.translation = translation;
+            transform.rotation = Quat::from_rotation_z(angle);
+        }
+        if projection.scale != letterbox.scale * scale {
+            projection.scale = letterbox.scale * scale;
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{util, AppState, FrameInput, Player, ReadyToJump, Tuning};
+use bevy::{math::Vec3Swizzles, prelude::*};
+
+/// A bar above the player filling up while a jump charges in
+/// [`JumpMode::Charge`](crate::settings::JumpMode::Charge).
+pub struct ChargeMeterPlugin;
+
+impl Plugin for ChargeMeterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::Restart).with_system(util::despawn_all::<ChargeMeter>),
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::Ready)
+                .label("ready_enter")
+                .with_system(ChargeMeter::spawn),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Ready)
+                .label("ready_update")
+                .with_system(ChargeMeter::update),
+        );
+    }
+}
+
+/// The meter's backdrop, or its fill when `fill` is set.
+#[derive(Component)]
+pub struct ChargeMeter {
+    fill: bool,
+}
+
+impl ChargeMeter {
+    /// Size of the backdrop in pixels, the fill is a pixel smaller on every side.
+    const WIDTH: f32 = 20.;
+    const HEIGHT: f32 = 4.;
+    /// Pixels above the player's center.
+    const OFFSET: f32 = 24.;
+    const Z: f32 = 6.;
+    const COLOR: Color = Color::rgb(0.45, 0.85, 0.25);
+    const FULL_COLOR: Color = Color::GOLD;
+
+    pub fn spawn(mut commands: Commands) {
+        for fill in [false, true] {
+            let (color, size, z) = if fill {
+                (
+                    Self::COLOR,
+                    Vec2::new(Self::WIDTH - 2., Self::HEIGHT - 2.),
+                    Self::Z + 0.1,
+                )
+            } else {
+                (
+                    Color::rgba(0., 0., 0., 0.7),
+                    Vec2::new(Self::WIDTH, Self::HEIGHT),
+                    Self::Z,
+                )
+            };
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color,
+                        custom_size: Some(size),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(0., 0., z),
+                    visibility: Visibility { is_visible: false },
+                    ..Default::default()
+                })
+                .insert(ChargeMeter { fill });
+        }
+    }
+
+    pub fn update(
+        mut q_meter: Query<(&ChargeMeter, &mut Sprite, &mut Transform, &mut Visibility)>,
+        q_player: Query<&Transform, (With<Player>, With<ReadyToJump>, Without<ChargeMeter>)>,
+        input: Res<FrameInput>,
+        tuning: Res<Tuning>,
+    ) {
+        let shown = match (input.charge, q_player.get_single()) {
+            (Some(secs), Ok(player)) => Some((tuning.charge.fraction(secs), player)),
+            _ => None,
+        };
+        for (meter, mut sprite, mut transform, mut visibility) in q_meter.iter_mut() {
+            visibility.is_visible = shown.is_some();
+            let (fraction, player) = match shown {
+                Some(shown) => shown,
+                None => continue,
+            };
+            let center = player.translation.xy() + Vec2::new(0., Self::OFFSET);
+            if meter.fill {
+                let width = (Self::WIDTH - 2.) * fraction;
+                sprite.custom_size = Some(Vec2::new(width, Self::HEIGHT - 2.));
+                sprite.color = if fraction >= 1. {
+                    Self::FULL_COLOR
+                } else {
+                    Self::COLOR
+                };
+                // Fills from the left.
+                let left = center.x - (Self::WIDTH - 2.) / 2.;
+                transform.translation.x = left + width / 2.;
+            } else {
+                transform.translation.x = center.x;
+            }
+            transform.translation.y = center.y;
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{AppState, FrameInput, ReplayMode, Stats, FONT_PATH, HALF_HEIGHT, HALF_WIDTH};
+use bevy::{app::AppExit, prelude::*};
+
+/// The secret ending: reached from the egg overlay, rolls the credits and then quits.
+pub struct CreditsPlugin;
+
+impl Plugin for CreditsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::Credits)
+                .with_system(Credits::record_secret)
+                .with_system(Credits::spawn),
+        )
+        .add_system_set(SystemSet::on_update(AppState::Credits).with_system(Credits::update));
+    }
+}
+
+const CREDITS: &str = "You managed to break out of the simulation!
+You can finally go outside and live your life!
+
+
+
+Ad_Opt
+
+by xbagon
+
+Made for the Bevy Jam #1
+
+Built with Bevy, bevy_rapier, bevy_ase and benimator
+Font: Iosevka
+
+Thank you for playing";
+
+#[derive(Component)]
+pub struct Credits {
+    timer: Timer,
+}
+
+impl Credits {
+    /// Pixels per second.
+    const SCROLL_SPEED: f32 = 20.;
+    /// Until the last line has left the screen.
+    const DURATION_SECS: f32 = 24.;
+    /// Keys quit early after this long, so the key that opened the credits does not skip them.
+    const SKIPPABLE_AFTER_SECS: f32 = 1.;
+
+    pub fn record_secret(mut stats: ResMut<Stats>, replay: Res<ReplayMode>) {
+        info!("Secret found");
+        stats.secret_found = true;
+        stats.save(&replay);
+    }
+
+    pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>) {
+        commands.spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK,
+                custom_size: Some(Vec2::new(HALF_WIDTH * 2., HALF_HEIGHT * 2.)),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(0., 0., 20.),
+            ..Default::default()
+        });
+
+        let text_style = TextStyle {
+            font: asset_server.load(FONT_PATH),
+            font_size: 10.0,
+            color: Color::GOLD,
+        };
+        let text_alignment = TextAlignment {
+            vertical: VerticalAlign::Top,
+            horizontal: HorizontalAlign::Center,
+        };
+        commands
+            .spawn_bundle(Text2dBundle {
+                // Starts just below the screen.
+                transform: Transform::from_xyz(0., -HALF_HEIGHT, 21.),
+                text: Text::with_section(String::from(CREDITS), text_style, text_alignment),
+                ..Default::default()
+            })
+            .insert(Credits {
+                timer: Timer::from_seconds(Self::DURATION_SECS, false),
+            });
+    }
+
+    pub fn update(
+        time: Res<Time>,
+        input: Res<FrameInput>,
+        mut q_credits: Query<(&mut Credits, &mut Transform)>,
+        mut ev_app_exit: EventWriter<AppExit>,
+    ) {
+        let (mut credits, mut transform) = q_credits.single_mut();
+        credits.timer.tick(time.delta());
+        transform.translation.y += Self::SCROLL_SPEED * time.delta_seconds();
+
+        let skipped = input.any_key && credits.timer.elapsed_secs() > Self::SKIPPABLE_AFTER_SECS;
+        if credits.timer.just_finished() || skipped {
+            info!("Leaving the simulation");
+            ev_app_exit.send(AppExit);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::{
+    asset::{AssetIo, AssetIoError},
+    prelude::*,
+    tasks::IoTaskPool,
+    utils::BoxedFuture,
+};
+use std::path::{Path, PathBuf};
+
+include!(concat!(env!("OUT_DIR"), "/embedded_assets.rs"));
+
+/// Serves the files of `assets/` from the executable instead of the file system.
+///
+/// Must be added before `AssetPlugin`, which otherwise creates an asset server reading from
+/// `assets/`.
+pub struct EmbeddedAssetsPlugin;
+
+impl Plugin for EmbeddedAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        let task_pool = app
+            .world
+            .get_resource::<IoTaskPool>()
+            .expect("`IoTaskPool` resource not found.")
+            .0
+            .clone();
+        info!("Using {} embedded assets", EMBEDDED_ASSETS.len());
+        app.insert_resource(AssetServer::new(EmbeddedAssetIo, task_pool));
+    }
+}
+
+/// Returns the embedded file at `path`, relative to `assets/`.
+pub fn get(path: &Path) -> Option<&'static [u8]> {
+    let key = key(path);
+    EMBEDDED_ASSETS
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, bytes)| *bytes)
+}
+
+// Embedded paths always use `/`.
+fn key(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub struct EmbeddedAssetIo;
+
+impl AssetIo for EmbeddedAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            get(path)
+                .map(|bytes| bytes.to_vec())
+                .ok_or_else(|| AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let dir = key(path);
+        let files = EMBEDDED_ASSETS
+            .iter()
+            .map(|(name, _)| PathBuf::from(name))
+            .filter(move |file| file.parent().map(key).as_deref() == Some(dir.as_str()));
+        Ok(Box::new(files))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        let prefix = format!("{}/", key(path));
+        EMBEDDED_ASSETS
+            .iter()
+            .any(|(name, _)| name.starts_with(&prefix))
+    }
+
+    // Embedded files never change.
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
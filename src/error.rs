@@ -0,0 +1,114 @@
+use crate::{AppState, FONT_PATH};
+use bevy::prelude::*;
+use std::fmt;
+
+#[derive(Clone, Debug)]
+pub enum AssetProblem {
+    /// The file could not be loaded.
+    MissingFile {
+        path: String,
+    },
+    MissingFrame {
+        path: String,
+        frame: usize,
+    },
+    MissingTag {
+        path: String,
+        tag: String,
+    },
+    /// The sprite manifest does not declare something a system asked for.
+    Undeclared {
+        name: String,
+        item: String,
+    },
+    /// The file did not finish loading in time.
+    TimedOut {
+        path: String,
+    },
+    /// The file loaded but its contents are invalid, the details are in the log.
+    Invalid {
+        path: String,
+    },
+}
+
+impl fmt::Display for AssetProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetProblem::MissingFile { path } => write!(f, "{}: could not be loaded", path),
+            AssetProblem::MissingFrame { path, frame } => {
+                write!(f, "{}: frame {} is missing", path, frame)
+            }
+            AssetProblem::MissingTag { path, tag } => {
+                write!(f, "{}: animation tag \"{}\" is missing", path, tag)
+            }
+            AssetProblem::Undeclared { name, item } => {
+                write!(f, "sprite manifest: `{}` does not declare {}", name, item)
+            }
+            AssetProblem::TimedOut { path } => write!(f, "{}: timed out while loading", path),
+            AssetProblem::Invalid { path } => write!(f, "{}: invalid, see the log", path),
+        }
+    }
+}
+
+/// Every problem that sent the game to [`AppState::Error`].
+#[derive(Default)]
+pub struct AssetProblems(pub Vec<AssetProblem>);
+
+#[derive(Component)]
+pub struct ErrorScreen;
+
+pub struct ErrorPlugin;
+
+impl Plugin for ErrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetProblems>()
+            .add_system_set(SystemSet::on_enter(AppState::Error).with_system(ErrorScreen::spawn));
+    }
+}
+
+impl AssetProblem {
+    /// Chain after systems that look up assets to show the problem instead of panicking.
+    pub fn report(
+        In(result): In<Result<(), AssetProblem>>,
+        mut problems: ResMut<AssetProblems>,
+        mut state: ResMut<State<AppState>>,
+    ) {
+        if let Err(problem) = result {
+            problems.0.push(problem);
+            // Several systems may fail in the same frame.
+            let _ = state.set(AppState::Error);
+        }
+    }
+}
+
+impl ErrorScreen {
+    pub fn spawn(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        problems: Res<AssetProblems>,
+    ) {
+        let mut message = String::from("Ad_Opt could not start:\n");
+        for problem in &problems.0 {
+            error!("{}", problem);
+            message.push('\n');
+            message.push_str(&problem.to_string());
+        }
+
+        let text_style = TextStyle {
+            font: asset_server.load(FONT_PATH),
+            font_size: 10.0,
+            color: Color::WHITE,
+        };
+        let text_alignment = TextAlignment {
+            vertical: VerticalAlign::Center,
+            horizontal: HorizontalAlign::Center,
+        };
+        commands
+            .spawn_bundle(Text2dBundle {
+                transform: Transform::from_xyz(0., 0., 20.),
+                text: Text::with_section(message, text_style, text_alignment),
+                ..Default::default()
+            })
+            .insert(ErrorScreen);
+    }
+}
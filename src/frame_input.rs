@@ -0,0 +1,164 @@
+use crate::{
+    actions::{Action, Devices},
+    aim::Aim,
+    settings::JumpMode,
+    AppState, Settings,
+};
+use bevy::{
+    input::{keyboard::KeyboardInput, ElementState},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+/// All input the gameplay systems read in one frame, either taken from the devices or from a
+/// replay.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FrameInput {
+    /// World position of the [`Aim`] when [`Action::Jump`] was pressed.
+    pub click: Option<[f32; 2]>,
+    /// Any key or gamepad button was pressed.
+    pub any_key: bool,
+    pub pause: bool,
+    /// Retry from the main round after the game ended.
+    #[serde(default)]
+    pub retry: bool,
+    /// Menu navigation.
+    #[serde(default)]
+    pub up: bool,
+    #[serde(default)]
+    pub down: bool,
+    #[serde(default)]
+    pub confirm: bool,
+    /// Seconds [`Action::Jump`] has been held in [`JumpMode::Charge`], including the frame it
+    /// was released on.
+    #[serde(default)]
+    pub charge: Option<f32>,
+    /// World position of the [`Aim`] when a charged jump was released.
+    #[serde(default)]
+    pub release: Option<[f32; 2]>,
+    /// The last key of [`FrameInput::SECRET`] was typed.
+    #[serde(default)]
+    pub egg: bool,
+}
+
+/// What [`FrameInput::read_live`] keeps between frames.
+#[derive(Default)]
+pub struct LiveInput {
+    /// Seconds [`Action::Jump`] has been held so far.
+    charge: Option<f32>,
+    /// The last keys typed, to spot [`FrameInput::SECRET`].
+    typed: Vec<KeyCode>,
+}
+
+/// Frames since the title menu opened, used to stamp recorded input.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SimulationFrame(pub u64);
+
+impl FrameInput {
+    /// Typed on the keyboard during a round, leads to the egg overlay.
+    pub const SECRET: [KeyCode; 10] = [
+        KeyCode::Up,
+        KeyCode::Up,
+        KeyCode::Down,
+        KeyCode::Down,
+        KeyCode::Left,
+        KeyCode::Right,
+        KeyCode::Left,
+        KeyCode::Right,
+        KeyCode::B,
+        KeyCode::A,
+    ];
+
+    pub fn is_empty(&self) -> bool {
+        *self == FrameInput::default()
+    }
+
+    pub fn read_live(
+        mut input: ResMut<FrameInput>,
+        state: Res<State<AppState>>,
+        buttons: Res<Input<MouseButton>>,
+        keys: Res<Input<KeyCode>>,
+        gamepad_buttons: Res<Input<GamepadButton>>,
+        settings: Res<Settings>,
+        aim: Res<Aim>,
+        time: Res<Time>,
+        mut live: Local<LiveInput>,
+        mut key_evr: EventReader<KeyboardInput>,
+    ) {
+        let key_presses: Vec<_> = key_evr
+            .iter()
+            .filter(|ev| ev.state == ElementState::Pressed)
+            .collect();
+        let any_key =
+            !key_presses.is_empty() || gamepad_buttons.get_just_pressed().next().is_some();
+        // Nothing is recorded before the title menu starts the [`SimulationFrame`].
+        if matches!(state.current(), AppState::Loading | AppState::Error) {
+            *input = FrameInput::default();
+            *live = LiveInput::default();
+            return;
+        }
+
+        let LiveInput { charge, typed } = &mut *live;
+        // Only the last keys typed can be the start of the secret.
+        typed.extend(key_presses.iter().filter_map(|ev| ev.key_code));
+        let excess = typed.len().saturating_sub(Self::SECRET.len());
+        typed.drain(..excess);
+        let egg = typed[..] == Self::SECRET;
+        if egg {
+            typed.clear();
+        }
+
+        let devices = Devices {
+            keys: &keys,
+            mouse: &buttons,
+            gamepad_buttons: &gamepad_buttons,
+        };
+        let pressed = |action| settings.bindings.just_pressed(action, &devices);
+        // W and S also move through the menus, unless they are bound to an action.
+        let fallback = |key| keys.just_pressed(key) && !settings.bindings.is_key_bound(key);
+
+        let mut release = None;
+        // A press in a menu must not start a charge that a later release in the round jumps with.
+        if settings.jump_mode != JumpMode::Charge || *state.current() != AppState::Ready {
+            *charge = None;
+        } else if pressed(Action::Jump) {
+            *charge = Some(0.);
+        } else if let Some(secs) = charge.as_mut() {
+            *secs += time.delta_seconds();
+            if !settings.bindings.pressed(Action::Jump, &devices) {
+                release = Some(aim.position.into());
+            }
+        }
+        let held = *charge;
+        if release.is_some() {
+            *charge = None;
+        }
+
+        *input = FrameInput {
+            click: pressed(Action::Jump).then(|| aim.position.into()),
+            any_key,
+            pause: pressed(Action::Pause),
+            retry: pressed(Action::Retry),
+            up: pressed(Action::Up) || fallback(KeyCode::W),
+            down: pressed(Action::Down) || fallback(KeyCode::S),
+            confirm: pressed(Action::Confirm),
+            charge: held,
+            release,
+            egg,
+        };
+    }
+}
+
+impl SimulationFrame {
+    pub fn start(mut commands: Commands, frame: Option<Res<SimulationFrame>>) {
+        if frame.is_none() {
+            commands.insert_resource(SimulationFrame(0));
+        }
+    }
+
+    pub fn advance(frame: Option<ResMut<SimulationFrame>>) {
+        if let Some(mut frame) = frame {
+            frame.0 += 1;
+        }
+    }
+}
@@ -1,20 +1,51 @@
 use crate::{
-    nalgebra::Isometry2, AppState, Monster, SlimeBall, TargetStatus, HALF_HEIGHT, HALF_WIDTH,
-    PHYSICS_SCALE,
-};
-use bevy::{
-    input::{keyboard::KeyboardInput, ElementState},
-    prelude::*,
+    nalgebra::Isometry2, util, AppState, AssetProblem, FrameInput, GameMode, Monster, SlimeBall,
+    SpriteManifest, TargetStatus, Tuning, FONT_PATH, HALF_HEIGHT, HALF_WIDTH,
 };
+use bevy::prelude::*;
 use bevy_ase::asset::AseFileMap;
 use bevy_rapier2d::prelude::*;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+pub struct GameLoopPlugin;
+
+impl Plugin for GameLoopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::Ready)
+                .label("ready_enter")
+                .with_system(Game::setup.chain(AssetProblem::report)),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Ready)
+                .label("ready_update")
+                .with_system(Game::update.label("Game::update")),
+        )
+        .add_system_set(SystemSet::on_enter(AppState::End).with_system(RestartHint::spawn))
+        .add_system_set(SystemSet::on_update(AppState::End).with_system(Game::retry))
+        .add_system_set(
+            SystemSet::on_enter(AppState::Restart)
+                .with_system(util::despawn_all::<Overlay>)
+                .with_system(util::despawn_all::<Wall>)
+                .with_system(util::despawn_all::<RestartHint>),
+        )
+        .add_system_set(SystemSet::on_update(AppState::Restart).with_system(Game::restart))
+        .add_system_set_to_stage(
+            CoreStage::PostUpdate,
+            SystemSet::on_update(AppState::Ready)
+                .label("ready_post_update")
+                .with_system(Game::detect_round_over),
+        )
+        .add_event::<Phase>()
+        .add_event::<Won>();
+    }
+}
 
 pub struct Game {
     phase: Phase,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Phase {
     Start,
     TransIntro,
@@ -26,10 +57,29 @@ pub enum Phase {
     Main,
     TransEnd,
     End,
+    TransEgg,
     Egg,
 }
 
-pub struct Won;
+/// A body part came to rest in the middle of the screen.
+pub struct Won {
+    /// In physics units.
+    pub position: Vec2,
+}
+
+/// How the next round starts after [`AppState::Restart`].
+pub struct Restart {
+    /// Start at [`Phase::TransMain`] instead of the press start overlay and the intro.
+    pub skip_intro: bool,
+    /// Go back to the title menu instead of starting another round.
+    pub to_menu: bool,
+}
+
+#[derive(Component)]
+pub struct Wall;
+
+#[derive(Component)]
+pub struct RestartHint;
 
 #[derive(Component)]
 pub struct Overlay {
@@ -40,15 +90,26 @@ pub struct Overlay {
 }
 
 impl Game {
-    pub fn setup(mut commands: Commands, ase_file_map: Res<AseFileMap>) {
-        let overlays_assets = ase_file_map
-            .get(Path::new("sprites/overlays.aseprite"))
-            .unwrap();
+    pub fn phase(&self) -> &Phase {
+        &self.phase
+    }
+
+    pub fn setup(
+        mut commands: Commands,
+        sprite_manifest: Res<SpriteManifest>,
+        ase_file_map: Res<AseFileMap>,
+        tuning: Res<Tuning>,
+        mode: Res<GameMode>,
+        restart: Option<Res<Restart>>,
+    ) -> Result<(), AssetProblem> {
+        let skip_intro =
+            *mode == GameMode::Endless || restart.map_or(false, |restart| restart.skip_intro);
+        let sheet = sprite_manifest.sheet(&ase_file_map, "overlays")?;
         let overlay = Overlay {
-            press_start_overlay: overlays_assets.texture(0).unwrap().clone(),
-            you_win_overlay: overlays_assets.texture(1).unwrap().clone(),
-            memory_overflow_overlay: overlays_assets.texture(2).unwrap().clone(),
-            egg_overlay: overlays_assets.texture(3).unwrap().clone(),
+            press_start_overlay: sheet.texture(0)?,
+            you_win_overlay: sheet.texture(1)?,
+            memory_overflow_overlay: sheet.texture(2)?,
+            egg_overlay: sheet.texture(3)?,
         };
 
         commands
@@ -59,106 +120,126 @@ impl Game {
                     scale: Vec3::splat(3.8),
                     ..Default::default()
                 },
+                visibility: Visibility {
+                    is_visible: !skip_intro,
+                },
                 ..Default::default()
             })
             .insert(overlay);
 
         commands.insert_resource(Game {
-            phase: Phase::Start,
+            phase: if skip_intro {
+                Phase::TransMain
+            } else {
+                Phase::Start
+            },
         });
         //TOP
-        commands.spawn_bundle(ColliderBundle {
-            position: Isometry2::new(
-                Vector::new(0.0, HALF_HEIGHT / PHYSICS_SCALE),
-                std::f32::consts::PI,
-            )
-            .into(),
-            shape: ColliderShape::heightfield(
-                DVector::from_element(2, 0.0),
-                Vector::new(HALF_WIDTH * 2.0 / PHYSICS_SCALE, 0.),
-            )
-            .into(),
-            flags: ColliderFlags {
-                solver_groups: InteractionGroups::new(1 << 0, !0),
-                collision_groups: InteractionGroups::new(1 << 0, !(1 << 5)),
+        commands
+            .spawn_bundle(ColliderBundle {
+                position: Isometry2::new(
+                    Vector::new(0.0, HALF_HEIGHT / tuning.physics_scale),
+                    std::f32::consts::PI,
+                )
+                .into(),
+                shape: ColliderShape::heightfield(
+                    DVector::from_element(2, 0.0),
+                    Vector::new(HALF_WIDTH * 2.0 / tuning.physics_scale, 0.),
+                )
+                .into(),
+                flags: ColliderFlags {
+                    solver_groups: InteractionGroups::new(1 << 0, !0),
+                    collision_groups: InteractionGroups::new(1 << 0, !(1 << 5)),
+                    ..Default::default()
+                }
+                .into(),
                 ..Default::default()
-            }
-            .into(),
-            ..Default::default()
-        });
+            })
+            .insert(Wall);
         //BOTTOM
-        commands.spawn_bundle(ColliderBundle {
-            position: Isometry2::new(Vector::new(0.0, -HALF_HEIGHT / PHYSICS_SCALE), 0.0).into(),
-            shape: ColliderShape::heightfield(
-                DVector::from_element(2, 0.0),
-                Vector::new(HALF_WIDTH * 2.0 / PHYSICS_SCALE, 0.),
-            )
-            .into(),
-            flags: ColliderFlags {
-                solver_groups: InteractionGroups::new(1 << 0, !0),
-                collision_groups: InteractionGroups::new(1 << 0, !(1 << 5)),
+        commands
+            .spawn_bundle(ColliderBundle {
+                position: Isometry2::new(
+                    Vector::new(0.0, -HALF_HEIGHT / tuning.physics_scale),
+                    0.0,
+                )
+                .into(),
+                shape: ColliderShape::heightfield(
+                    DVector::from_element(2, 0.0),
+                    Vector::new(HALF_WIDTH * 2.0 / tuning.physics_scale, 0.),
+                )
+                .into(),
+                flags: ColliderFlags {
+                    solver_groups: InteractionGroups::new(1 << 0, !0),
+                    collision_groups: InteractionGroups::new(1 << 0, !(1 << 5)),
+                    ..Default::default()
+                }
+                .into(),
                 ..Default::default()
-            }
-            .into(),
-            ..Default::default()
-        });
+            })
+            .insert(Wall);
         //LEFT
-        commands.spawn_bundle(ColliderBundle {
-            position: Isometry2::new(
-                Vector::new(-HALF_WIDTH / PHYSICS_SCALE, 0.),
-                std::f32::consts::FRAC_PI_2,
-            )
-            .into(),
-            shape: ColliderShape::heightfield(
-                DVector::from_element(2, 0.0),
-                Vector::new(HALF_HEIGHT * 2.0 / PHYSICS_SCALE, 0.),
-            )
-            .into(),
-            flags: ColliderFlags {
-                solver_groups: InteractionGroups::new(1 << 0, !0),
-                collision_groups: InteractionGroups::new(1 << 0, !(1 << 5)),
+        commands
+            .spawn_bundle(ColliderBundle {
+                position: Isometry2::new(
+                    Vector::new(-HALF_WIDTH / tuning.physics_scale, 0.),
+                    std::f32::consts::FRAC_PI_2,
+                )
+                .into(),
+                shape: ColliderShape::heightfield(
+                    DVector::from_element(2, 0.0),
+                    Vector::new(HALF_HEIGHT * 2.0 / tuning.physics_scale, 0.),
+                )
+                .into(),
+                flags: ColliderFlags {
+                    solver_groups: InteractionGroups::new(1 << 0, !0),
+                    collision_groups: InteractionGroups::new(1 << 0, !(1 << 5)),
+                    ..Default::default()
+                }
+                .into(),
                 ..Default::default()
-            }
-            .into(),
-            ..Default::default()
-        });
+            })
+            .insert(Wall);
         //RIGHT
-        commands.spawn_bundle(ColliderBundle {
-            position: Isometry2::new(
-                Vector::new(HALF_WIDTH / PHYSICS_SCALE, 0.),
-                -std::f32::consts::FRAC_PI_2,
-            )
-            .into(),
-            shape: ColliderShape::heightfield(
-                DVector::from_element(2, 0.0),
-                Vector::new(HALF_HEIGHT * 2.0 / PHYSICS_SCALE, 0.),
-            )
-            .into(),
-            flags: ColliderFlags {
-                solver_groups: InteractionGroups::new(1 << 0, !0),
-                collision_groups: InteractionGroups::new(1 << 0, !(1 << 5)),
+        commands
+            .spawn_bundle(ColliderBundle {
+                position: Isometry2::new(
+                    Vector::new(HALF_WIDTH / tuning.physics_scale, 0.),
+                    -std::f32::consts::FRAC_PI_2,
+                )
+                .into(),
+                shape: ColliderShape::heightfield(
+                    DVector::from_element(2, 0.0),
+                    Vector::new(HALF_HEIGHT * 2.0 / tuning.physics_scale, 0.),
+                )
+                .into(),
+                flags: ColliderFlags {
+                    solver_groups: InteractionGroups::new(1 << 0, !0),
+                    collision_groups: InteractionGroups::new(1 << 0, !(1 << 5)),
+                    ..Default::default()
+                }
+                .into(),
                 ..Default::default()
-            }
-            .into(),
-            ..Default::default()
-        });
+            })
+            .insert(Wall);
+        Ok(())
     }
 
     pub fn update(
         mut game: ResMut<Game>,
-        keys: Res<Input<KeyCode>>,
-        mut key_evr: EventReader<KeyboardInput>,
+        input: Res<FrameInput>,
         mut q_overlay: Query<(&Overlay, &mut Handle<Image>, &mut Visibility)>,
         mut q_monster: Query<&mut Monster>,
         mut ev_target_status: EventWriter<TargetStatus>,
         mut ev_phase: EventWriter<Phase>,
         mut ev_won: EventReader<Won>,
         mut state: ResMut<State<AppState>>,
+        mode: Res<GameMode>,
     ) {
         ev_phase.send(game.phase.clone());
         match game.phase {
             Phase::Start => {
-                if key_evr.iter().any(|ev| ev.state == ElementState::Pressed) {
+                if input.any_key {
                     let (_, _, mut visibility) = q_overlay.single_mut();
                     visibility.is_visible = false;
                     game.phase = Phase::TransIntro;
@@ -178,7 +259,7 @@ impl Game {
                 game.phase = Phase::Dead;
             }
             Phase::Dead => {
-                if key_evr.iter().any(|ev| ev.state == ElementState::Pressed) {
+                if input.any_key {
                     let (_, _, mut visibility) = q_overlay.single_mut();
                     visibility.is_visible = false;
                     game.phase = Phase::TransMain;
@@ -192,7 +273,8 @@ impl Game {
                 game.phase = Phase::TransMain2;
             }
             Phase::TransMain2 => {
-                if ev_won.iter().next().is_some() {
+                // Endless mode never ends.
+                if ev_won.iter().next().is_some() && *mode == GameMode::Story {
                     game.phase = Phase::TransEnd;
                 } else {
                     game.phase = Phase::Main;
@@ -205,20 +287,58 @@ impl Game {
                 game.phase = Phase::End;
             }
             Phase::End => {
-                state.set(AppState::End).unwrap();
+                if let Err(err) = state.set(AppState::End) {
+                    warn!("Failed to end the round: {:?}", err);
+                }
+            }
+            Phase::TransEgg => {
+                let (overlay, mut handle, mut visibility) = q_overlay.single_mut();
+                *handle = overlay.egg_overlay.clone();
+                visibility.is_visible = true;
+                game.phase = Phase::Egg;
             }
             Phase::Egg => {
-                if key_evr.iter().any(|ev| ev.state == ElementState::Pressed) {
-                    panic!("You managed to break out of the simulation! You can finally go outside and live your life!")
+                if input.any_key {
+                    if let Err(err) = state.set(AppState::Credits) {
+                        warn!("Failed to show the credits: {:?}", err);
+                    }
                 }
             }
             _ => {}
         }
-        if keys.just_pressed(KeyCode::Escape) {
-            let (overlay, mut handle, mut visibility) = q_overlay.single_mut();
-            *handle = overlay.egg_overlay.clone();
-            visibility.is_visible = true;
-            game.phase = Phase::Egg;
+        if input.egg && !matches!(game.phase, Phase::TransEgg | Phase::Egg) {
+            game.phase = Phase::TransEgg;
+        }
+    }
+
+    pub fn retry(
+        mut commands: Commands,
+        input: Res<FrameInput>,
+        mut state: ResMut<State<AppState>>,
+    ) {
+        // Escape is also any key.
+        if input.pause || input.retry || input.any_key {
+            commands.insert_resource(Restart {
+                skip_intro: input.retry,
+                to_menu: input.pause,
+            });
+            state.set(AppState::Restart).unwrap();
+        }
+    }
+
+    pub fn restart(
+        mut commands: Commands,
+        restart: Option<Res<Restart>>,
+        mut state: ResMut<State<AppState>>,
+    ) {
+        if restart.map_or(false, |restart| restart.to_menu) {
+            info!("Back to the title menu");
+            // The next round starts from the mode picked there.
+            commands.remove_resource::<Restart>();
+            state.set(AppState::Menu).unwrap();
+        } else {
+            info!("Restarting");
+            state.set(AppState::Ready).unwrap();
         }
     }
 
@@ -235,3 +355,31 @@ impl Game {
         }
     }
 }
+
+impl RestartHint {
+    pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>) {
+        let text_style = TextStyle {
+            font: asset_server.load(FONT_PATH),
+            font_size: 10.0,
+            color: Color::GOLD,
+        };
+        let text_alignment = TextAlignment {
+            vertical: VerticalAlign::Center,
+            horizontal: HorizontalAlign::Center,
+        };
+        commands
+            .spawn_bundle(Text2dBundle {
+                transform: Transform::from_xyz(0., -HALF_HEIGHT + 16., 20.),
+                text: Text::with_section(
+                    String::from(
+                        "Press any key to play again, R to retry from the main round, \
+                         Escape for the main menu",
+                    ),
+                    text_style,
+                    text_alignment,
+                ),
+                ..Default::default()
+            })
+            .insert(RestartHint);
+    }
+}
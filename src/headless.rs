@@ -0,0 +1,31 @@
+use crate::GamePlugin;
+use bevy::{
+    asset::AssetPlugin, input::InputPlugin, prelude::*, transform::TransformPlugin,
+    window::WindowPlugin,
+};
+
+/// Runs the game without a window or GPU.
+///
+/// Build an `App` with this plugin instead of `DefaultPlugins` + [`GamePlugin`] and step it
+/// with `app.update()` to advance the simulation one frame at a time.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MinimalPlugins);
+        #[cfg(feature = "embedded_assets")]
+        app.add_plugin(crate::embedded_assets::EmbeddedAssetsPlugin);
+        app.add_plugin(TransformPlugin)
+            .add_plugin(InputPlugin)
+            .add_plugin(WindowPlugin {
+                add_primary_window: false,
+                exit_on_close: false,
+            })
+            .add_plugin(AssetPlugin)
+            // Normally registered by the render, sprite and text plugins.
+            .add_asset::<Image>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<Font>()
+            .add_plugin(GamePlugin);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{MainCamera, HALF_HEIGHT, HALF_WIDTH};
+use bevy::prelude::*;
+
+/// Keeps the whole 16:9 playfield in view at any window size, and covers the rest of the window
+/// with black bars.
+pub struct LetterboxPlugin;
+
+impl Plugin for LetterboxPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Letterbox>()
+            .add_system(Letterbox::fit.before("CameraEffects::update"))
+            .add_system(LetterboxBar::update.after("CameraEffects::update"));
+    }
+}
+
+/// The camera keeps `ScalingMode::FixedVertical`, which shows the full height and as much width
+/// as the window has. Windows narrower than 16:9 get a larger scale, so the full width shows
+/// instead.
+pub struct Letterbox {
+    /// The projection scale without zoom.
+    pub scale: f32,
+}
+
+/// A bar along one side of the playfield, a child of the camera.
+#[derive(Component)]
+pub struct LetterboxBar {
+    offset: Vec2,
+}
+
+impl Default for Letterbox {
+    fn default() -> Self {
+        Letterbox { scale: HALF_HEIGHT }
+    }
+}
+
+impl Letterbox {
+    pub fn fit(mut letterbox: ResMut<Letterbox>, windows: Res<Windows>) {
+        // There is no window when running headless.
+        let window = match windows.get_primary() {
+            Some(window) if window.width() > 0. && window.height() > 0. => window,
+            _ => return,
+        };
+        let aspect_ratio = window.width() / window.height();
+        let scale = HALF_HEIGHT * (HALF_WIDTH / HALF_HEIGHT / aspect_ratio).max(1.);
+        if letterbox.scale != scale {
+            letterbox.scale = scale;
+        }
+    }
+}
+
+impl LetterboxBar {
+    /// Deep enough to cover 8:1 windows.
+    const DEPTH: f32 = HALF_WIDTH * 8.;
+    /// In front of everything the camera sees.
+    const Z: f32 = -1.;
+
+    pub fn spawn(parent: &mut ChildBuilder) {
+        let size = Vec2::splat(Self::DEPTH);
+        for side in [Vec2::X, -Vec2::X, Vec2::Y, -Vec2::Y] {
+            let offset = side * (Vec2::new(HALF_WIDTH, HALF_HEIGHT) + size / 2.);
+            parent
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::BLACK,
+                        custom_size: Some(size),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(offset.extend(Self::Z)),
+                    ..Default::default()
+                })
+                .insert(LetterboxBar { offset });
+        }
+    }
+
+    /// Keeps the bars at the edges of the playfield while the camera zooms.
+    pub fn update(
+        letterbox: Res<Letterbox>,
+        q_camera: Query<&OrthographicProjection, With<MainCamera>>,
+        mut q_bar: Query<(&LetterboxBar, &mut Transform)>,
+    ) {
+        let projection = match q_camera.get_single() {
+            Ok(projection) => projection,
+            Err(_) => return,
+        };
+        let zoom = projection.scale / letterbox.scale;
+        for (bar, mut transform) in q_bar.iter_mut() {
+            let translation = (bar.offset * zoom).extend(Self::Z);
+            if transform.translation != translation {
+                transform.translation = translation;
+                transform.scale = Vec3::new(zoom, zoom, 1.);
+            }
+        }
+    }
+}
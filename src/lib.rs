@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+use bevy_ase::{self, loader};
+
+pub use actions::{Action, Bindings};
+pub use aim::{Aim, AimPlugin};
+pub use audio::{PlaySound, SoundPlugin};
+use bevy_rapier2d::prelude::*;
+pub use body_part::{BodyPart, BodyPartPlugin, BodyPartTextures};
+pub use camera::CameraEffectsPlugin;
+pub use charge_meter::ChargeMeterPlugin;
+pub use credits::CreditsPlugin;
+pub use error::{AssetProblem, AssetProblems, ErrorPlugin};
+pub use frame_input::FrameInput;
+pub use game::{Game, GameLoopPlugin};
+pub use headless::HeadlessPlugin;
+pub use letterbox::LetterboxPlugin;
+pub use loading::LoadingPlugin;
+pub use main_menu::{GameMode, MainMenuPlugin};
+pub use menu::{MenuAction, MenuPlugin};
+pub use monster::{Monster, MonsterPlugin};
+pub use mouse_cursor::MouseCursor;
+pub use music::MusicPlugin;
+pub use particles::ParticlePlugin;
+pub use pause::PausePlugin;
+pub use player::{Player, PlayerPlugin};
+pub use player_damaged::PlayerDamaged;
+pub use ready_to_jump::ReadyToJump;
+pub use replay::{ReplayMode, ReplayPlugin};
+pub use rng::{GameRng, RngPlugin, RngSeed};
+pub use score::{Score, ScorePlugin};
+pub use settings::{Settings, SettingsPlugin};
+pub use slime_ball::{SlimeBall, SlimeBallBundle, SlimeBallPlugin, SpawnSlimeBall};
+pub use sprites::{SpriteManifest, SpriteManifestPlugin};
+pub use stats::{Stats, StatsPlugin};
+pub use target_status::TargetStatus;
+pub use telegraph::TelegraphPlugin;
+pub use trajectory::TrajectoryPlugin;
+pub use tuning::{Tuning, TuningPlugin};
+
+pub mod actions;
+pub mod aim;
+pub mod audio;
+pub mod body_part;
+pub mod camera;
+pub mod charge_meter;
+pub mod credits;
+#[cfg(feature = "embedded_assets")]
+pub mod embedded_assets;
+pub mod error;
+pub mod frame_input;
+pub mod game;
+pub mod headless;
+pub mod letterbox;
+pub mod loading;
+pub mod main_menu;
+pub mod menu;
+pub mod monster;
+pub mod mouse_cursor;
+pub mod music;
+pub mod particles;
+pub mod pause;
+pub mod player;
+pub mod player_damaged;
+pub mod ready_to_jump;
+pub mod replay;
+pub mod rng;
+pub mod score;
+pub mod settings;
+pub mod slime_ball;
+pub mod sprites;
+pub mod stats;
+pub mod target_status;
+pub mod telegraph;
+pub mod trajectory;
+pub mod tuning;
+pub mod util;
+
+pub const FONT_PATH: &str = "fonts/iosevka-extendedsemibold.ttf";
+pub const HALF_HEIGHT: f32 = 128.;
+pub const HALF_WIDTH: f32 = HALF_HEIGHT * (16. / 9.);
+
+/// Everything the game needs on top of either `DefaultPlugins` or [`HeadlessPlugin`].
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(loader::AseLoaderDefaultPlugin)
+            .add_plugin(benimator::AnimationPlugin::default())
+            .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
+            // Scale and gravity are set from the `Tuning` once it is loaded.
+            .insert_resource(RapierConfiguration::default())
+            .add_state(AppState::Loading)
+            .add_state_to_stage(CoreStage::PostUpdate, AppState::Loading)
+            // The cursor is also used in the menus, so it lives from the title menu on.
+            .add_system_set(
+                SystemSet::on_enter(AppState::Menu)
+                    .label("menu_enter")
+                    .with_system(MouseCursor::spawn.chain(AssetProblem::report)),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::Ready)
+                    .label("ready_enter")
+                    .with_system(MouseCursor::reset),
+            )
+            .add_system(MouseCursor::update)
+            .add_system_set(
+                SystemSet::on_update(AppState::Ready)
+                    .label("ready_update")
+                    .with_system(ReadyToJump::update)
+                    .with_system(TargetStatus::changed.label("TargetStatus::changed")),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Ready)
+                    .after("ready_update")
+                    .with_system(util::DespawnEntity::handle_event),
+            )
+            .add_event::<TargetStatus>()
+            .add_event::<util::DespawnEntity>()
+            .add_plugin(LoadingPlugin)
+            .add_plugin(ErrorPlugin)
+            .add_plugin(SpriteManifestPlugin)
+            .add_plugin(TuningPlugin)
+            .add_plugin(AimPlugin)
+            .add_plugin(ReplayPlugin)
+            .add_plugin(RngPlugin)
+            .add_plugin(StatsPlugin)
+            .add_plugin(SettingsPlugin)
+            .add_plugin(SoundPlugin)
+            .add_plugin(MusicPlugin)
+            .add_plugin(GameLoopPlugin)
+            .add_plugin(MenuPlugin)
+            .add_plugin(MainMenuPlugin)
+            .add_plugin(PausePlugin)
+            .add_plugin(CreditsPlugin)
+            .add_plugin(SlimeBallPlugin)
+            .add_plugin(ParticlePlugin)
+            .add_plugin(CameraEffectsPlugin)
+            .add_plugin(LetterboxPlugin)
+            .add_plugin(MonsterPlugin)
+            .add_plugin(PlayerPlugin)
+            .add_plugin(ChargeMeterPlugin)
+            .add_plugin(TrajectoryPlugin)
+            .add_plugin(TelegraphPlugin)
+            .add_plugin(ScorePlugin)
+            .add_plugin(BodyPartPlugin);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AppState {
+    Loading,
+    /// The title menu, where the [`GameMode`] is picked.
+    Menu,
+    Ready,
+    End,
+    /// The secret ending, reached from the egg overlay. Quits when it is over.
+    Credits,
+    /// The settings screen, pushed on top of [`AppState::Menu`] or [`AppState::Paused`].
+    Settings,
+    /// Pushed on top of [`AppState::Ready`] and popped to resume.
+    Paused,
+    /// Despawns everything spawned when entering [`AppState::Ready`], then enters it again or
+    /// goes back to [`AppState::Menu`].
+    Restart,
+    /// Shows the [`AssetProblems`] that stopped the game from starting.
+    Error,
+}
+
+#[derive(Component)]
+pub struct MainCamera;
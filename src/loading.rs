@@ -0,0 +1,323 @@
+use crate::{
+    audio::Sounds,
+    letterbox::LetterboxBar,
+    sprites::{SpriteManifestHandle, SpriteSheetHandles},
+    tuning::TuningHandle,
+    util, AppState, AssetProblem, AssetProblems, MainCamera, SpriteManifest, Tuning, FONT_PATH,
+    HALF_HEIGHT,
+};
+use bevy::{asset::LoadState, prelude::*, render::camera::ScalingMode};
+use bevy_ase::{
+    asset::{Animation, AseFileMap},
+    loader::Loader,
+};
+use std::path::Path;
+
+/// Gives up on assets that are still loading after this long.
+pub const LOADING_TIMEOUT_SECS: f32 = 30.;
+
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadingStatus>()
+            .insert_resource(LoadingTimeout(Timer::from_seconds(
+                LOADING_TIMEOUT_SECS,
+                false,
+            )))
+            .add_system_set(
+                SystemSet::on_enter(AppState::Loading)
+                    .label("loading_enter")
+                    .with_system(LoadingTimeout::reset)
+                    .with_system(load_assets)
+                    .with_system(SpriteManifest::load)
+                    .with_system(Tuning::load)
+                    .with_system(Sounds::load)
+                    .with_system(LoadingScreen::spawn),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Loading)
+                    .label("loading_update")
+                    .with_system(load_sprite_sheets.before("LoadingStatus::update"))
+                    .with_system(LoadingStatus::update.label("LoadingStatus::update"))
+                    .with_system(LoadingTimeout::tick.label("LoadingTimeout::tick"))
+                    .with_system(
+                        check_loading
+                            .after("LoadingStatus::update")
+                            .after("LoadingTimeout::tick"),
+                    )
+                    .with_system(LoadingScreen::update.after("LoadingStatus::update")),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Loading)
+                    .with_system(Tuning::configure_physics)
+                    .with_system(util::despawn_all::<LoadingScreen>),
+            );
+    }
+}
+
+pub struct FontHandle(pub Handle<Font>);
+
+/// Only ticks in [`AppState::Loading`] and restarts whenever it is entered.
+pub struct LoadingTimeout(pub Timer);
+
+#[derive(Default)]
+pub struct LoadingStatus {
+    pub total: usize,
+    /// Paths of the assets that are still loading.
+    pub pending: Vec<String>,
+    pub failed: Vec<AssetProblem>,
+}
+
+#[derive(Component)]
+pub struct LoadingScreen;
+
+#[derive(Component)]
+pub struct LoadingBar;
+
+// Load the font, the sprite sheets follow once the manifest is loaded.
+pub fn load_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
+    info!("Loading assets");
+    commands.insert_resource(FontHandle(asset_server.load(FONT_PATH)));
+
+    let mut camera = OrthographicCameraBundle::new_2d();
+    camera.orthographic_projection.scale = HALF_HEIGHT;
+    camera.orthographic_projection.scaling_mode = ScalingMode::FixedVertical;
+    commands
+        .spawn_bundle(camera)
+        .insert(MainCamera)
+        .with_children(LetterboxBar::spawn);
+}
+
+// Send every sheet in the manifest to the loader, once.
+pub fn load_sprite_sheets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut aseloader: ResMut<Loader>,
+    manifest_handle: Res<SpriteManifestHandle>,
+    manifests: Res<Assets<SpriteManifest>>,
+    sprite_sheet_handles: Option<Res<SpriteSheetHandles>>,
+) {
+    if sprite_sheet_handles.is_some() {
+        return;
+    }
+    let manifest = match manifests.get(&manifest_handle.0) {
+        Some(manifest) => manifest,
+        None => return,
+    };
+
+    let mut handles = Vec::new();
+    for sheet in &manifest.sheets {
+        let handle = asset_server.load(Path::new(&sheet.path));
+        aseloader.add(handle.clone());
+        handles.push((sheet.path.clone(), handle));
+    }
+    commands.insert_resource(SpriteSheetHandles(handles));
+    commands.insert_resource(manifest.clone());
+}
+
+// Wait until all assets are loaded, then check that they contain everything the game reads from
+// them.
+pub fn check_loading(
+    mut commands: Commands,
+    mut state: ResMut<State<AppState>>,
+    status: Res<LoadingStatus>,
+    tuning_handle: Res<TuningHandle>,
+    tunings: Res<Assets<Tuning>>,
+    sprite_manifest: Option<Res<SpriteManifest>>,
+    ase_file_map: Res<AseFileMap>,
+    animations: Res<Assets<Animation>>,
+    mut problems: ResMut<AssetProblems>,
+    timeout: Res<LoadingTimeout>,
+) {
+    // Problems can also be found before loading, e.g. in the replay passed on the command line.
+    if !status.failed.is_empty() || !problems.0.is_empty() {
+        problems.0.extend(status.failed.iter().cloned());
+        state.set(AppState::Error).unwrap();
+        return;
+    }
+
+    if !status.pending.is_empty() {
+        if timeout.0.finished() {
+            problems.0.extend(
+                status
+                    .pending
+                    .iter()
+                    .map(|path| AssetProblem::TimedOut { path: path.clone() }),
+            );
+            state.set(AppState::Error).unwrap();
+        }
+        return;
+    }
+
+    // Inserted together with the sheet handles, so it is there once nothing is pending.
+    let sprite_manifest = match sprite_manifest {
+        Some(sprite_manifest) => sprite_manifest,
+        None => return,
+    };
+    info!("All assets loaded");
+    let invalid = sprite_manifest.validate(&ase_file_map, &animations);
+    if !invalid.is_empty() {
+        problems.0.extend(invalid);
+        state.set(AppState::Error).unwrap();
+        return;
+    }
+    if let Some(tuning) = tunings.get(&tuning_handle.handle) {
+        commands.insert_resource(tuning.clone());
+    }
+    state.set(AppState::Menu).unwrap()
+}
+
+impl LoadingTimeout {
+    pub fn reset(mut timeout: ResMut<LoadingTimeout>) {
+        timeout.0.reset();
+    }
+
+    pub fn tick(mut timeout: ResMut<LoadingTimeout>, time: Res<Time>) {
+        timeout.0.tick(time.delta());
+    }
+}
+
+impl LoadingStatus {
+    pub fn loaded(&self) -> usize {
+        self.total - self.pending.len() - self.failed.len()
+    }
+
+    pub fn update(
+        mut status: ResMut<LoadingStatus>,
+        asset_server: Res<AssetServer>,
+        manifest_handle: Res<SpriteManifestHandle>,
+        sprite_sheet_handles: Option<Res<SpriteSheetHandles>>,
+        ase_file_map: Res<AseFileMap>,
+        tuning_handle: Res<TuningHandle>,
+        tunings: Res<Assets<Tuning>>,
+        font_handle: Res<FontHandle>,
+        sounds: Res<Sounds>,
+    ) {
+        let mut pending = Vec::new();
+        let mut failed = Vec::new();
+
+        if asset_server.get_load_state(&manifest_handle.0) == LoadState::Failed {
+            failed.push(AssetProblem::Invalid {
+                path: SpriteManifest::PATH.to_owned(),
+            });
+        } else if sprite_sheet_handles.is_none() {
+            pending.push(SpriteManifest::PATH.to_owned());
+        }
+        let sheets = sprite_sheet_handles
+            .as_ref()
+            .map_or(&[][..], |sprite_sheet_handles| &sprite_sheet_handles.0);
+        for (path, handle) in sheets {
+            if asset_server.get_load_state(handle) == LoadState::Failed {
+                failed.push(AssetProblem::MissingFile { path: path.clone() });
+            // Sprite sheets are only usable once the loader has imported them.
+            } else if ase_file_map.get(Path::new(path)).is_none() {
+                pending.push(path.clone());
+            }
+        }
+
+        if asset_server.get_load_state(&tuning_handle.handle) == LoadState::Failed {
+            failed.push(AssetProblem::Invalid {
+                path: tuning_handle.path.clone(),
+            });
+        } else if tunings.get(&tuning_handle.handle).is_none() {
+            pending.push(tuning_handle.path.clone());
+        }
+
+        match asset_server.get_load_state(&font_handle.0) {
+            LoadState::Loaded => {}
+            LoadState::Failed => failed.push(AssetProblem::MissingFile {
+                path: FONT_PATH.to_owned(),
+            }),
+            _ => pending.push(FONT_PATH.to_owned()),
+        }
+
+        for (path, handle) in sounds.handles() {
+            match asset_server.get_load_state(handle) {
+                LoadState::Loaded => {}
+                LoadState::Failed => failed.push(AssetProblem::MissingFile {
+                    path: path.to_owned(),
+                }),
+                _ => pending.push(path.to_owned()),
+            }
+        }
+
+        *status = LoadingStatus {
+            // The manifest, the sheets it lists, the tuning, the font, the sounds and the music.
+            total: sheets.len() + 3 + sounds.effects.len() + sounds.tracks.len(),
+            pending,
+            failed,
+        };
+    }
+}
+
+impl LoadingScreen {
+    const BAR_WIDTH: f32 = 200.;
+
+    pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>) {
+        let text_style = TextStyle {
+            font: asset_server.load(FONT_PATH),
+            font_size: 10.0,
+            color: Color::GOLD,
+        };
+        let text_alignment = TextAlignment {
+            vertical: VerticalAlign::Center,
+            horizontal: HorizontalAlign::Center,
+        };
+        commands
+            .spawn_bundle(Text2dBundle {
+                transform: Transform::from_xyz(0., 12., 20.),
+                text: Text::with_section(String::from("Loading"), text_style, text_alignment),
+                ..Default::default()
+            })
+            .insert(LoadingScreen);
+
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::DARK_GRAY,
+                    custom_size: Some(Vec2::new(Self::BAR_WIDTH, 6.)),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(0., 0., 20.),
+                ..Default::default()
+            })
+            .insert(LoadingScreen);
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::GOLD,
+                    custom_size: Some(Vec2::new(0., 6.)),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(-Self::BAR_WIDTH / 2., 0., 21.),
+                ..Default::default()
+            })
+            .insert(LoadingScreen)
+            .insert(LoadingBar);
+    }
+
+    pub fn update(
+        status: Res<LoadingStatus>,
+        mut q_text: Query<&mut Text, With<LoadingScreen>>,
+        mut q_bar: Query<(&mut Sprite, &mut Transform), With<LoadingBar>>,
+    ) {
+        if status.total == 0 {
+            return;
+        }
+        let progress = status.loaded() as f32 / status.total as f32;
+
+        for mut text in q_text.iter_mut() {
+            text.sections[0].value = match status.pending.first() {
+                Some(path) => format!("Loading {}/{}: {}", status.loaded(), status.total, path),
+                None => format!("Loading {}/{}", status.loaded(), status.total),
+            };
+        }
+        for (mut sprite, mut transform) in q_bar.iter_mut() {
+            let width = Self::BAR_WIDTH * progress;
+            sprite.custom_size = Some(Vec2::new(width, 6.));
+            // Sprites are centered, keep the bar growing from the left.
+            transform.translation.x = (width - Self::BAR_WIDTH) / 2.;
+        }
+    }
+}
@@ -1,133 +1,24 @@
-use std::path::Path;
-
-use bevy::{prelude::*, render::camera::ScalingMode};
-use bevy_ase::{
-    self,
-    loader::{self, Loader},
-};
+use bevy::prelude::*;
+use bevy_jam_one::{GamePlugin, Settings};
 
 //use bevy_inspector_egui::WorldInspectorPlugin;
-use bevy_rapier2d::prelude::*;
-pub use body_part::{BodyPart, BodyPartTextures};
-pub use game::Game;
-pub use monster::Monster;
-pub use mouse_cursor::MouseCursor;
-pub use player::Player;
-pub use player_damaged::PlayerDamaged;
-pub use ready_to_jump::ReadyToJump;
-pub use score::Score;
-pub use slime_ball::{SlimeBall, SlimeBallBundle, SpawnSlimeBall};
-pub use target_status::TargetStatus;
-
-mod body_part;
-mod game;
-mod monster;
-mod mouse_cursor;
-mod player;
-mod player_damaged;
-mod ready_to_jump;
-mod score;
-mod slime_ball;
-mod target_status;
-mod util;
-
-const PHYSICS_SCALE: f32 = 10.0;
-const HALF_HEIGHT: f32 = 128.;
-const HALF_WIDTH: f32 = HALF_HEIGHT * (16. / 9.);
 
 fn main() {
+    let settings = Settings::load();
     App::new()
-        .insert_resource(WindowDescriptor {
-            title: String::from("Ad_Opt"),
-            width: 1280.,
-            height: 720.,
-            cursor_visible: false,
-            ..Default::default()
+        .insert_resource(settings.window_descriptor())
+        .insert_resource(settings)
+        .add_plugins_with(DefaultPlugins, |group| {
+            #[cfg(feature = "embedded_assets")]
+            group.add_before::<bevy::asset::AssetPlugin, _>(
+                bevy_jam_one::embedded_assets::EmbeddedAssetsPlugin,
+            );
+            group
         })
-        .add_plugins(DefaultPlugins)
-        .add_plugin(loader::AseLoaderDefaultPlugin)
-        .add_plugin(benimator::AnimationPlugin::default())
-        .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
         .insert_resource(Msaa::default())
-        .insert_resource(RapierConfiguration {
-            scale: PHYSICS_SCALE,
-            gravity: Vector::new(0.0, -10.0),
-            ..Default::default()
-        })
+        .add_plugin(GamePlugin)
         //.add_plugin(WorldInspectorPlugin::new())
         .add_startup_system(set_window_icon)
-        .add_state(AppState::Loading)
-        .add_state_to_stage(CoreStage::PostUpdate, AppState::Loading)
-        .add_system_set(
-            SystemSet::on_enter(AppState::Loading)
-                .label("loading_enter")
-                .with_system(load_sprites),
-        )
-        .add_system_set(
-            SystemSet::on_update(AppState::Loading)
-                .label("loading_update")
-                .with_system(check_loading_sprites),
-        )
-        .add_system_set(
-            SystemSet::on_enter(AppState::Ready)
-                .label("ready_enter")
-                .with_system(Game::setup)
-                .with_system(SlimeBallBundle::setup)
-                .with_system(BodyPart::setup)
-                .with_system(MouseCursor::spawn)
-                .with_system(Player::spawn)
-                .with_system(Monster::spawn)
-                .with_system(Score::spawn),
-        )
-        .add_system_set(
-            SystemSet::on_update(AppState::Ready)
-                .label("ready_update")
-                .with_system(Game::update.label("Game::update"))
-                .with_system(MouseCursor::update)
-                .with_system(ReadyToJump::update)
-                .with_system(SlimeBall::update.after("SpawnSlimeBall::handle_event"))
-                .with_system(SlimeBall::on_contact_stopped)
-                .with_system(SlimeBall::on_contact_started.label("SlimeBall::on_contact_started"))
-                .with_system(
-                    PlayerDamaged::handle_event
-                        .before("TargetStatus::changed")
-                        .after("SlimeBall::on_contact_started"),
-                )
-                .with_system(TargetStatus::changed.label("TargetStatus::changed"))
-                .with_system(SpawnSlimeBall::handle_event.label("SpawnSlimeBall::handle_event"))
-                .with_system(Score::on_player_damaged.after("SlimeBall::on_contact_started"))
-                .with_system(Player::on_damaged.after("SlimeBall::on_contact_started"))
-                .with_system(Player::on_phase)
-                .with_system(
-                    BodyPart::win_check
-                        .label("BodyPart::win_check")
-                        .before("Game::update"),
-                )
-                .with_system(
-                    Score::on_end
-                        .after("BodyPart::win_check")
-                        .before("Game::update"),
-                ),
-        )
-        .add_system_set(
-            SystemSet::on_update(AppState::Ready)
-                .after("ready_update")
-                .with_system(util::DespawnEntity::handle_event),
-        )
-        .add_system_set_to_stage(
-            CoreStage::PostUpdate,
-            SystemSet::on_update(AppState::Ready)
-                .label("ready_post_update")
-                .with_system(Monster::animation_finished)
-                .with_system(Game::detect_round_over),
-        )
-        .add_system_set(SystemSet::on_update(AppState::End).with_system(MouseCursor::update))
-        .add_event::<TargetStatus>()
-        .add_event::<PlayerDamaged>()
-        .add_event::<util::DespawnEntity>()
-        .add_event::<SpawnSlimeBall>()
-        .add_event::<game::Phase>()
-        .add_event::<game::Won>()
         .run()
 }
 
@@ -140,9 +31,13 @@ fn set_window_icon(windows: Res<bevy::winit::WinitWindows>) {
     // here we use the `image` crate to load our icon data from a png file
     // this is not a very bevy-native solution, but it will do
     let (icon_rgba, icon_width, icon_height) = {
-        let image = image::open("assets/icon.ico")
-            .expect("Failed to open icon path")
-            .into_rgba8();
+        let image = match load_icon() {
+            Ok(image) => image.into_rgba8(),
+            Err(err) => {
+                warn!("Failed to load the window icon: {}", err);
+                return;
+            }
+        };
         let (width, height) = image.dimensions();
         let rgba = image.into_raw();
         (rgba, width, height)
@@ -153,49 +48,18 @@ fn set_window_icon(windows: Res<bevy::winit::WinitWindows>) {
     primary.set_window_icon(Some(icon));
 }
 
-#[cfg(target_arch = "wasm32")]
-fn set_window_icon(windows: Res<bevy::winit::WinitWindows>) {}
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub enum AppState {
-    Loading,
-    Ready,
-    End,
+#[cfg(all(not(target_arch = "wasm32"), feature = "embedded_assets"))]
+fn load_icon() -> image::ImageResult<image::DynamicImage> {
+    let bytes = bevy_jam_one::embedded_assets::get(std::path::Path::new("icon.ico"))
+        .expect("icon.ico is embedded");
+    image::load_from_memory(bytes)
 }
 
-#[derive(Component)]
-pub struct MainCamera;
-
-// Collect the sprite and send it to the loader.
-pub fn load_sprites(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut aseloader: ResMut<Loader>,
-) {
-    info!("Loading assets");
-    let person_player = asset_server.load(Path::new("sprites/person_player.aseprite"));
-    aseloader.add(person_player);
-    let slime_ball = asset_server.load(Path::new("sprites/slime_ball.aseprite"));
-    aseloader.add(slime_ball);
-    let monster = asset_server.load(Path::new("sprites/monster.aseprite"));
-    aseloader.add(monster);
-    let target = asset_server.load(Path::new("sprites/target.aseprite"));
-    aseloader.add(target);
-    let target = asset_server.load(Path::new("sprites/overlays.aseprite"));
-    aseloader.add(target);
-    let target = asset_server.load(Path::new("sprites/body_parts.aseprite"));
-    aseloader.add(target);
-
-    let mut camera = OrthographicCameraBundle::new_2d();
-    camera.orthographic_projection.scale = HALF_HEIGHT;
-    camera.orthographic_projection.scaling_mode = ScalingMode::FixedVertical;
-    commands.spawn_bundle(camera).insert(MainCamera);
+// Next to the other assets, which are not necessarily in the working directory.
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "embedded_assets")))]
+fn load_icon() -> image::ImageResult<image::DynamicImage> {
+    image::open(bevy::asset::FileAssetIo::get_root_path().join("assets/icon.ico"))
 }
 
-// Wait until all sprites are loaded.
-pub fn check_loading_sprites(mut state: ResMut<State<AppState>>, ase_loader: Res<Loader>) {
-    if ase_loader.is_loaded() {
-        info!("All Aseprite files loaded");
-        state.set(AppState::Ready).unwrap()
-    }
-}
+#[cfg(target_arch = "wasm32")]
+fn set_window_icon(windows: Res<bevy::winit::WinitWindows>) {}
@@ -0,0 +1,127 @@
+use crate::{
+    menu::{Menu, MenuAction},
+    AppState, Stats,
+};
+use bevy::{app::AppExit, prelude::*};
+
+/// The title menu shown after loading.
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameMode>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::Menu)
+                    .label("menu_enter")
+                    .with_system(MainMenu::spawn),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Menu)
+                    .with_system(MainMenu::on_menu.after("Menu::navigate")),
+            )
+            .add_system_set(SystemSet::on_pause(AppState::Menu).with_system(Menu::despawn))
+            .add_system_set(SystemSet::on_resume(AppState::Menu).with_system(MainMenu::spawn))
+            .add_system_set(SystemSet::on_exit(AppState::Menu).with_system(Menu::despawn));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameMode {
+    /// Intro, then the main round until the memory overflows.
+    Story,
+    /// Only the main round, over and over.
+    Endless,
+}
+
+impl Default for GameMode {
+    fn default() -> Self {
+        GameMode::Story
+    }
+}
+
+pub struct MainMenu;
+
+impl MainMenu {
+    pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>) {
+        Self::spawn_title(&mut commands, &asset_server);
+    }
+
+    fn spawn_title(commands: &mut Commands, asset_server: &AssetServer) {
+        Menu::spawn(
+            commands,
+            asset_server,
+            "Ad_Opt",
+            &[
+                ("Story", MenuAction::Story, true),
+                ("Endless", MenuAction::Endless, true),
+                ("Settings", MenuAction::Settings, true),
+                ("Statistics", MenuAction::Statistics, true),
+                ("Quit", MenuAction::Quit, true),
+            ],
+            None,
+        );
+    }
+
+    fn spawn_statistics(commands: &mut Commands, asset_server: &AssetServer, stats: &Stats) {
+        let lines = [
+            format!("Games played: {}", stats.games_played),
+            format!("Story completed: {}", stats.story_completed),
+            format!("Highest score: -{:04}", stats.highest_score),
+            format!(
+                "Secret: {}",
+                if stats.secret_found { "found" } else { "???" }
+            ),
+        ];
+        let mut items: Vec<_> = lines
+            .iter()
+            .map(|line| (line.as_str(), MenuAction::Back, false))
+            .collect();
+        items.push(("Back", MenuAction::Back, true));
+        Menu::spawn(
+            commands,
+            asset_server,
+            "Statistics",
+            &items,
+            Some(MenuAction::Back),
+        );
+    }
+
+    pub fn on_menu(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        mut ev_menu_action: EventReader<MenuAction>,
+        q_menu: Query<Entity, With<Menu>>,
+        stats: Res<Stats>,
+        mut state: ResMut<State<AppState>>,
+        mut ev_app_exit: EventWriter<AppExit>,
+    ) {
+        for action in ev_menu_action.iter() {
+            match action {
+                MenuAction::Story | MenuAction::Endless => {
+                    let mode = if *action == MenuAction::Story {
+                        GameMode::Story
+                    } else {
+                        GameMode::Endless
+                    };
+                    info!("Starting {:?}", mode);
+                    commands.insert_resource(mode);
+                    state.set(AppState::Ready).unwrap();
+                }
+                MenuAction::Statistics | MenuAction::Back => {
+                    for e in q_menu.iter() {
+                        commands.entity(e).despawn_recursive();
+                    }
+                    if *action == MenuAction::Statistics {
+                        Self::spawn_statistics(&mut commands, &asset_server, &stats);
+                    } else {
+                        Self::spawn_title(&mut commands, &asset_server);
+                    }
+                }
+                MenuAction::Settings => state.push(AppState::Settings).unwrap(),
+                MenuAction::Quit => ev_app_exit.send(AppExit),
+                _ => {}
+            }
+            break;
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{settings::Setting, FrameInput, FONT_PATH, HALF_HEIGHT, HALF_WIDTH};
+use bevy::{math::Vec3Swizzles, prelude::*};
+
+/// Keyboard, mouse and gamepad navigation for the menu on screen. Only one menu is expected at a time,
+/// the state showing it reads the [`MenuAction`] events.
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MenuAction>()
+            .add_system(Menu::navigate.label("Menu::navigate"))
+            .add_system(Menu::highlight.after("Menu::navigate"));
+    }
+}
+
+/// Sent when a menu item is chosen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuAction {
+    Story,
+    Endless,
+    Statistics,
+    Back,
+    Resume,
+    Restart,
+    Settings,
+    Setting(Setting),
+    /// Leaves the round for the title menu.
+    MainMenu,
+    Quit,
+}
+
+#[derive(Component)]
+pub struct Menu {
+    selected: usize,
+    /// Sent when escape is pressed.
+    cancel: Option<MenuAction>,
+    /// Input is ignored on the first frame, the key that opened the menu would otherwise be
+    /// read again.
+    ready: bool,
+    /// Sent on the next frame, so the input that chose it is not read again by the systems that
+    /// run once the menu is closed.
+    chosen: Option<MenuAction>,
+    /// Ignores input, e.g. while a key is being rebound.
+    pub locked: bool,
+}
+
+#[derive(Component)]
+pub struct MenuItem {
+    pub index: usize,
+    pub action: MenuAction,
+    pub enabled: bool,
+}
+
+impl MenuItem {
+    /// Half the clickable area around an item's center.
+    const HALF_WIDTH: f32 = 60.;
+    const HALF_HEIGHT: f32 = 7.;
+}
+
+impl Menu {
+    const Z: f32 = 9.5;
+    const ITEM_SPACING: f32 = 14.;
+    /// From the first item to the title's center.
+    const TITLE_OFFSET: f32 = 20.;
+    /// Kept free above the first item for the title, and the same below the last.
+    const TITLE_SPACE: f32 = 34.;
+    const COLUMN_WIDTH: f32 = 220.;
+
+    /// Spawns a backdrop with the title and items as children.
+    pub fn spawn(
+        commands: &mut Commands,
+        asset_server: &AssetServer,
+        title: &str,
+        items: &[(&str, MenuAction, bool)],
+        cancel: Option<MenuAction>,
+    ) -> Entity {
+        let font = asset_server.load(FONT_PATH);
+        let text_alignment = TextAlignment {
+            vertical: VerticalAlign::Center,
+            horizontal: HorizontalAlign::Center,
+        };
+        let text = |value: &str, font_size: f32, y: f32| Text2dBundle {
+            transform: Transform::from_xyz(0., y, 0.1),
+            text: Text::with_section(
+                String::from(value),
+                TextStyle {
+                    font: font.clone(),
+                    font_size,
+                    color: Color::WHITE,
+                },
+                text_alignment,
+            ),
+            ..Default::default()
+        };
+
+        // Items that do not fit under the title go into more columns, read top to bottom.
+        let max_rows = ((HALF_HEIGHT - Self::TITLE_SPACE) * 2. / Self::ITEM_SPACING) as usize;
+        let columns = ((items.len() + max_rows - 1) / max_rows).max(1);
+        let rows = (items.len() + columns - 1) / columns;
+        let top = (rows as f32 * Self::ITEM_SPACING) / 2.;
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(0., 0., 0., 0.7),
+                    custom_size: Some(Vec2::new(HALF_WIDTH * 2., HALF_HEIGHT * 2.)),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(0., 0., Self::Z),
+                ..Default::default()
+            })
+            .insert(Menu {
+                selected: items
+                    .iter()
+                    .position(|(_, _, enabled)| *enabled)
+                    .unwrap_or(0),
+                cancel,
+                ready: false,
+                chosen: None,
+                locked: false,
+            })
+            .with_children(|parent| {
+                parent.spawn_bundle(text(title, 20., top + Self::TITLE_OFFSET));
+                for (index, (label, action, enabled)) in items.iter().enumerate() {
+                    let column = (index / rows) as f32 - (columns - 1) as f32 / 2.;
+                    let row = (index % rows) as f32;
+                    let mut item = text(label, 12., top - row * Self::ITEM_SPACING);
+                    item.transform.translation.x = column * Self::COLUMN_WIDTH;
+                    parent.spawn_bundle(item).insert(MenuItem {
+                        index,
+                        action: *action,
+                        enabled: *enabled,
+                    });
+                }
+            })
+            .id()
+    }
+
+    pub fn despawn(mut commands: Commands, q_menu: Query<Entity, With<Menu>>) {
+        for e in q_menu.iter() {
+            commands.entity(e).despawn_recursive();
+        }
+    }
+
+    pub fn navigate(
+        input: Res<FrameInput>,
+        mut q_menu: Query<&mut Menu>,
+        q_item: Query<(&MenuItem, &GlobalTransform)>,
+        mut ev_menu_action: EventWriter<MenuAction>,
+    ) {
+        let mut menu = match q_menu.iter_mut().next() {
+            Some(menu) => menu,
+            None => return,
+        };
+        if !menu.ready {
+            menu.ready = true;
+            return;
+        }
+        if menu.locked {
+            return;
+        }
+        if let Some(action) = menu.chosen.take() {
+            ev_menu_action.send(action);
+            return;
+        }
+
+        let mut enabled: Vec<_> = q_item.iter().filter(|(item, _)| item.enabled).collect();
+        enabled.sort_by_key(|(item, _)| item.index);
+        if enabled.is_empty() {
+            return;
+        }
+
+        if let Some(click) = input.click {
+            let click = Vec2::from(click);
+            let clicked = enabled.iter().find(|(_, transform)| {
+                let offset = (click - transform.translation.xy()).abs();
+                offset.x < MenuItem::HALF_WIDTH && offset.y < MenuItem::HALF_HEIGHT
+            });
+            if let Some((item, _)) = clicked {
+                menu.selected = item.index;
+                menu.chosen = Some(item.action);
+                return;
+            }
+        }
+
+        if input.pause {
+            if let Some(cancel) = menu.cancel {
+                menu.chosen = Some(cancel);
+                return;
+            }
+        }
+
+        let current = enabled
+            .iter()
+            .position(|(item, _)| item.index == menu.selected)
+            .unwrap_or(0);
+        if input.confirm {
+            menu.chosen = Some(enabled[current].0.action);
+        } else if input.up {
+            menu.selected = enabled[(current + enabled.len() - 1) % enabled.len()]
+                .0
+                .index;
+        } else if input.down {
+            menu.selected = enabled[(current + 1) % enabled.len()].0.index;
+        }
+    }
+
+    pub fn highlight(q_menu: Query<&Menu>, mut q_item: Query<(&MenuItem, &mut Text)>) {
+        let menu = match q_menu.iter().next() {
+            Some(menu) => menu,
+            None => return,
+        };
+        for (item, mut text) in q_item.iter_mut() {
+            text.sections[0].style.color = if !item.enabled {
+                Color::DARK_GRAY
+            } else if item.index == menu.selected {
+                Color::GOLD
+            } else {
+                Color::WHITE
+            };
+        }
+    }
+}
@@ -1,8 +1,31 @@
-use crate::{util, Score, SpawnSlimeBall};
+use crate::{
+    audio::{PlaySound, Sound},
+    util, AppState, AssetProblem, ReplayMode, Score, SpawnSlimeBall, SpriteManifest, Tuning,
+};
 use benimator::SpriteSheetAnimation;
 use bevy::prelude::*;
 use bevy_ase::asset::{Animation, AseFileMap};
-use std::path::Path;
+
+pub struct MonsterPlugin;
+
+impl Plugin for MonsterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::Restart).with_system(util::despawn_all::<Monster>),
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::Ready)
+                .label("ready_enter")
+                .with_system(Monster::spawn.chain(AssetProblem::report)),
+        )
+        .add_system_set_to_stage(
+            CoreStage::PostUpdate,
+            SystemSet::on_update(AppState::Ready)
+                .label("ready_post_update")
+                .with_system(Monster::animation_finished),
+        );
+    }
+}
 
 #[derive(Component)]
 pub struct Monster {
@@ -22,22 +45,20 @@ pub enum Phase {
 impl Monster {
     pub fn spawn(
         mut commands: Commands,
+        sprite_manifest: Res<SpriteManifest>,
         ase_file_map: Res<AseFileMap>,
         animations: Res<Assets<Animation>>,
         mut sprite_sheet_animations: ResMut<Assets<benimator::SpriteSheetAnimation>>,
-    ) {
-        let asset_map = ase_file_map
-            .get(Path::new("sprites/monster.aseprite"))
-            .unwrap();
+    ) -> Result<(), AssetProblem> {
+        let sheet = sprite_manifest.sheet(&ase_file_map, "monster")?;
 
-        let (texture_atlas, anim) = util::Animation::get_components(&animations, asset_map, "Idle");
+        let (texture_atlas, anim) = sheet.animation(&animations, "Idle")?;
         let idle_animation = util::Animation::from_components(
             texture_atlas,
             sprite_sheet_animations.add(anim.once()),
         );
 
-        let (texture_atlas, anim) =
-            util::Animation::get_components(&animations, asset_map, "Shoot");
+        let (texture_atlas, anim) = sheet.animation(&animations, "Shoot")?;
         let shoot_animation = util::Animation::from_components(
             texture_atlas,
             sprite_sheet_animations.add(anim.once()),
@@ -56,6 +77,7 @@ impl Monster {
                 shoot_animation,
                 phase: Phase::Idle,
             });
+        Ok(())
     }
 
     pub fn animation_finished(
@@ -67,7 +89,10 @@ impl Monster {
         )>,
         removed_play: RemovedComponents<benimator::Play>,
         mut ev_spawn_slime_ball: EventWriter<SpawnSlimeBall>,
+        mut ev_play_sound: EventWriter<PlaySound>,
         q_score: Query<&Score>,
+        replay: Res<ReplayMode>,
+        tuning: Res<Tuning>,
     ) {
         for e in removed_play.iter() {
             if let Ok((mut monster, mut texture_atlas, mut sprite_sheet_animation)) =
@@ -87,11 +112,15 @@ impl Monster {
                         monster.phase = Phase::Shoot;
                     }
                     Phase::Shoot => {
-                        ev_spawn_slime_ball.send(SpawnSlimeBall {
-                            position: None,
-                            velocity: None,
-                            health: 100 + q_score.single().0 as i32 * q_score.single().0 as i32,
-                        });
+                        ev_play_sound.send(PlaySound::new(Sound::Shoot));
+                        // During playback the recorded shots are sent instead.
+                        if !replay.is_playback() {
+                            ev_spawn_slime_ball.send(SpawnSlimeBall {
+                                position: None,
+                                velocity: None,
+                                health: tuning.slime_ball_health(q_score.single().0),
+                            });
+                        }
                         monster
                             .idle_animation
                             .apply_animation(&mut texture_atlas, &mut sprite_sheet_animation);
@@ -1,7 +1,6 @@
-use crate::{util::screen_to_world_pos, MainCamera};
+use crate::{aim::Aim, settings::CursorMode, AssetProblem, Settings, SpriteManifest};
 use bevy::prelude::*;
 use bevy_ase::asset::AseFileMap;
-use std::path::Path;
 
 #[derive(Component)]
 pub struct MouseCursor {
@@ -10,14 +9,21 @@ pub struct MouseCursor {
 }
 
 impl MouseCursor {
-    pub fn spawn(mut commands: Commands, ase_file_map: Res<AseFileMap>) {
+    pub fn spawn(
+        mut commands: Commands,
+        sprite_manifest: Res<SpriteManifest>,
+        ase_file_map: Res<AseFileMap>,
+        q_cursor: Query<(), With<MouseCursor>>,
+    ) -> Result<(), AssetProblem> {
+        // Still there when coming back to the title menu.
+        if q_cursor.iter().next().is_some() {
+            return Ok(());
+        }
         //commands.spawn().insert(Game);
-        let target_assets = ase_file_map
-            .get(Path::new("sprites/target.aseprite"))
-            .unwrap();
+        let sheet = sprite_manifest.sheet(&ase_file_map, "target")?;
         let cursor = MouseCursor {
-            active_sprite: target_assets.texture(0).unwrap().clone(),
-            inactive_sprite: target_assets.texture(1).unwrap().clone(),
+            active_sprite: sheet.texture(0)?,
+            inactive_sprite: sheet.texture(1)?,
         };
         commands
             .spawn_bundle(SpriteBundle {
@@ -25,19 +31,28 @@ impl MouseCursor {
                 ..Default::default()
             })
             .insert(cursor);
+        Ok(())
+    }
+
+    /// A new round starts without a jump target.
+    pub fn reset(mut q_cursor: Query<(&MouseCursor, &mut Handle<Image>)>) {
+        for (cursor, mut texture) in q_cursor.iter_mut() {
+            *texture = cursor.inactive_sprite.clone();
+        }
     }
 
     pub fn update(
-        mut q_cursor: Query<&mut Transform, With<MouseCursor>>,
-        q_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
-        windows: Res<Windows>,
+        mut q_cursor: Query<(&mut Transform, &mut Visibility), With<MouseCursor>>,
+        aim: Res<Aim>,
+        settings: Res<Settings>,
     ) {
-        let (camera, camera_transform) = q_camera.single();
-        let window = windows.get(camera.window).unwrap();
-        debug_assert_eq!(window.id(), windows.get_primary().unwrap().id());
-
-        if let Some(world_pos) = screen_to_world_pos(camera, camera_transform, window) {
-            q_cursor.single_mut().translation = world_pos.extend(10.);
-        }
+        // Not spawned yet while loading.
+        let (mut transform, mut visibility) = match q_cursor.get_single_mut() {
+            Ok(cursor) => cursor,
+            Err(_) => return,
+        };
+        // The gamepad moves the target sprite even with the system cursor.
+        visibility.is_visible = settings.cursor == CursorMode::Game || aim.gamepad;
+        transform.translation = aim.position.extend(10.);
     }
 }
@@ -0,0 +1,212 @@
+use crate::{audio::Sounds, game, AppState, Score, Settings, SlimeBall, Tuning};
+use bevy::{prelude::*, utils::HashMap};
+use bevy_kira_audio::{Audio, AudioChannel};
+
+/// Crossfades to the [`Track`] of each [`game::Phase`] and adds intensity layers to the main
+/// track as the slime balls and the score pile up.
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Music>()
+            .add_system_set(
+                SystemSet::on_update(AppState::Ready)
+                    .label("ready_update")
+                    .with_system(Music::on_phase.after("Game::update").before("Music::fade")),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::Menu)
+                    .label("menu_enter")
+                    .with_system(Music::fade_out),
+            )
+            // Keeps fading while paused and after the game ended.
+            .add_system(Music::fade.label("Music::fade"));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Track {
+    Start,
+    Intro,
+    Dead,
+    Main,
+    End,
+    /// Intensity layers, played in sync with [`Track::Main`].
+    Drums,
+    Bass,
+}
+
+/// The tracks playing, each in its own channel, since `bevy_kira_audio` only sets volumes per
+/// channel.
+pub struct Music {
+    /// The track fading in, `None` for silence.
+    current: Option<Track>,
+    /// The volume of every playing track, from 0 to 1 before the settings.
+    volumes: HashMap<Track, f32>,
+    channels: HashMap<Track, AudioChannel>,
+}
+
+impl Track {
+    pub const ALL: [Track; 7] = [
+        Track::Start,
+        Track::Intro,
+        Track::Dead,
+        Track::Main,
+        Track::End,
+        Track::Drums,
+        Track::Bass,
+    ];
+    pub const LAYERS: [Track; 2] = [Track::Drums, Track::Bass];
+
+    pub fn path(self) -> &'static str {
+        match self {
+            Track::Start => "sounds/music/start.wav",
+            Track::Intro => "sounds/music/intro.wav",
+            Track::Dead => "sounds/music/dead.wav",
+            Track::Main => "sounds/music/main.wav",
+            Track::End => "sounds/music/end.wav",
+            Track::Drums => "sounds/music/drums.wav",
+            Track::Bass => "sounds/music/bass.wav",
+        }
+    }
+
+    /// The index into the layer thresholds of the tuning.
+    fn layer(self) -> Option<usize> {
+        Self::LAYERS.iter().position(|layer| *layer == self)
+    }
+
+    /// The main track starts and stops together with its layers, which keeps them in sync.
+    fn with_layers(self) -> Vec<Track> {
+        let mut tracks = vec![self];
+        if self == Track::Main {
+            tracks.extend(Self::LAYERS);
+        }
+        tracks
+    }
+
+    /// `None` cuts to silence.
+    fn for_phase(phase: &game::Phase) -> Option<Track> {
+        match phase {
+            game::Phase::Start => Some(Track::Start),
+            game::Phase::TransIntro | game::Phase::Intro => Some(Track::Intro),
+            game::Phase::TransDead | game::Phase::Dead => Some(Track::Dead),
+            game::Phase::TransMain | game::Phase::TransMain2 | game::Phase::Main => {
+                Some(Track::Main)
+            }
+            game::Phase::TransEnd | game::Phase::End => Some(Track::End),
+            game::Phase::TransEgg | game::Phase::Egg => None,
+        }
+    }
+}
+
+impl Default for Music {
+    fn default() -> Self {
+        Music {
+            current: None,
+            volumes: HashMap::default(),
+            channels: Track::ALL
+                .iter()
+                .map(|track| (*track, AudioChannel::new(format!("music_{:?}", track))))
+                .collect(),
+        }
+    }
+}
+
+impl Music {
+    pub fn on_phase(
+        mut ev_phase: EventReader<game::Phase>,
+        mut music: ResMut<Music>,
+        audio: Res<Audio>,
+    ) {
+        for ev in ev_phase.iter() {
+            let track = Track::for_phase(ev);
+            if track == music.current {
+                continue;
+            }
+            info!("Music: {:?}", track);
+            let music = &mut *music;
+            music.current = track;
+            // The egg overlay cuts the music instead of fading it out.
+            if track.is_none() {
+                for (track, _) in music.volumes.drain() {
+                    audio.stop_channel(&music.channels[&track]);
+                }
+            }
+        }
+    }
+
+    /// The title menu is silent.
+    pub fn fade_out(mut music: ResMut<Music>) {
+        music.current = None;
+    }
+
+    pub fn fade(
+        mut music: ResMut<Music>,
+        sounds: Option<Res<Sounds>>,
+        tuning: Option<Res<Tuning>>,
+        audio: Res<Audio>,
+        settings: Res<Settings>,
+        time: Res<Time>,
+        q_slime_ball: Query<(), With<SlimeBall>>,
+        q_score: Query<&Score>,
+    ) {
+        let (sounds, tuning) = match (sounds, tuning) {
+            (Some(sounds), Some(tuning)) => (sounds, tuning),
+            _ => return,
+        };
+        let slime_balls = q_slime_ball.iter().count();
+        let score = q_score.iter().next().map_or(0, |score| score.0);
+        let step = time.delta_seconds() / tuning.sound.crossfade_secs;
+        let volume_scale = settings.music_volume * settings.master_volume;
+
+        for track in Track::ALL {
+            let wanted = match track.layer() {
+                Some(layer) => {
+                    music.current == Some(Track::Main)
+                        && (slime_balls >= tuning.sound.layer_slime_balls[layer]
+                            || score >= tuning.sound.layer_scores[layer])
+                }
+                None => music.current == Some(track),
+            };
+            let volume = match music.volumes.get(&track) {
+                Some(volume) => *volume,
+                // Layers start and stop with the main track.
+                None if wanted && track.layer().is_none() => {
+                    music.start(&audio, &sounds, track);
+                    0.
+                }
+                None => continue,
+            };
+
+            let faded = if wanted {
+                (volume + step).min(1.)
+            } else {
+                (volume - step).max(0.)
+            };
+            if faded == 0. && !wanted && track.layer().is_none() {
+                music.stop(&audio, track);
+                continue;
+            }
+            if faded != volume || settings.is_changed() {
+                audio.set_volume_in_channel(faded * volume_scale, &music.channels[&track]);
+            }
+            music.volumes.insert(track, faded);
+        }
+    }
+
+    fn start(&mut self, audio: &Audio, sounds: &Sounds, track: Track) {
+        for track in track.with_layers() {
+            let channel = &self.channels[&track];
+            audio.set_volume_in_channel(0., channel);
+            audio.play_looped_in_channel(sounds.tracks[&track].clone(), channel);
+            self.volumes.insert(track, 0.);
+        }
+    }
+
+    fn stop(&mut self, audio: &Audio, track: Track) {
+        for track in track.with_layers() {
+            audio.stop_channel(&self.channels[&track]);
+            self.volumes.remove(&track);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{tuning::ParticleTuning, util, AppState, Tuning};
+use bevy::prelude::*;
+use rand::Rng;
+
+/// Slime droplets, plain sprites moved on the CPU.
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnParticles>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::Restart).with_system(util::despawn_all::<Particle>),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Ready)
+                    .label("ready_update")
+                    .with_system(
+                        SpawnParticles::handle_event
+                            .after("SlimeBall::on_contact_started")
+                            .after("PlayerDamaged::handle_event"),
+                    )
+                    .with_system(Particle::update),
+            )
+            // Lets the last droplets land once the game ended.
+            .add_system_set(SystemSet::on_update(AppState::End).with_system(Particle::update));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticleKind {
+    /// A slime ball bouncing off something.
+    Spray,
+    /// A slime ball splitting in two.
+    Burst,
+    /// A slime ball running out of health.
+    Splat,
+}
+
+/// Sent to emit droplets. Positions and velocities are in physics units.
+pub struct SpawnParticles {
+    pub kind: ParticleKind,
+    pub position: Vec2,
+    /// The droplets fly in this direction, spread by the kind's tuning.
+    pub velocity: Vec2,
+}
+
+#[derive(Component)]
+pub struct Particle {
+    /// Pixels per second.
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+impl Particle {
+    const Z: f32 = 4.;
+    const SIZE: f32 = 2.;
+    const COLOR: Color = Color::rgb(0.45, 0.85, 0.25);
+
+    pub fn update(
+        mut commands: Commands,
+        mut q_particle: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+        time: Res<Time>,
+        tuning: Res<Tuning>,
+    ) {
+        let gravity = Vec2::new(0., tuning.gravity * tuning.physics_scale);
+        for (entity, mut particle, mut transform, mut sprite) in q_particle.iter_mut() {
+            if particle.lifetime.tick(time.delta()).finished() {
+                commands.entity(entity).despawn();
+                continue;
+            }
+            particle.velocity += gravity * time.delta_seconds();
+            transform.translation += (particle.velocity * time.delta_seconds()).extend(0.);
+            sprite.color.set_a(1. - particle.lifetime.percent());
+        }
+    }
+}
+
+impl SpawnParticles {
+    pub fn handle_event(
+        mut commands: Commands,
+        mut ev_spawn_particles: EventReader<SpawnParticles>,
+        tuning: Res<Tuning>,
+    ) {
+        // Only for looks, so it does not draw from the `GameRng` and leaves replays alone.
+        let mut rng = rand::thread_rng();
+        for ev in ev_spawn_particles.iter() {
+            let ParticleTuning {
+                count,
+                lifetime,
+                speed,
+                spread,
+            } = match ev.kind {
+                ParticleKind::Spray => tuning.particles.spray,
+                ParticleKind::Burst => tuning.particles.burst,
+                ParticleKind::Splat => tuning.particles.splat,
+            };
+            let position = ev.position * tuning.physics_scale;
+            let direction = ev.velocity.y.atan2(ev.velocity.x);
+
+            for _ in 0..count {
+                let angle = direction + rng.gen_range(-0.5f32..=0.5) * spread;
+                let velocity =
+                    Vec2::new(angle.cos(), angle.sin()) * speed * rng.gen_range(0.3f32..=1.);
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            color: Particle::COLOR,
+                            custom_size: Some(Vec2::splat(Particle::SIZE)),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_translation(position.extend(Particle::Z)),
+                        ..Default::default()
+                    })
+                    .insert(Particle {
+                        velocity,
+                        lifetime: Timer::from_seconds(lifetime * rng.gen_range(0.5f32..=1.), false),
+                    });
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{
+    camera::CameraEffects,
+    game::{Phase, Restart},
+    menu::{Menu, MenuAction},
+    AppState, FrameInput, Game,
+};
+use bevy::{app::AppExit, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(AppState::Ready)
+                .label("ready_update")
+                .with_system(Pause::on_escape.after("Game::update")),
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::Paused)
+                .with_system(Pause::freeze)
+                .with_system(Pause::spawn_menu),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Paused)
+                .with_system(Pause::on_menu.after("Menu::navigate")),
+        )
+        .add_system_set(SystemSet::on_pause(AppState::Paused).with_system(Menu::despawn))
+        .add_system_set(SystemSet::on_resume(AppState::Paused).with_system(Pause::spawn_menu))
+        .add_system_set(
+            SystemSet::on_exit(AppState::Paused)
+                .with_system(Pause::thaw)
+                .with_system(Menu::despawn),
+        );
+    }
+}
+
+/// Pauses the game on escape and shows the pause menu.
+pub struct Pause;
+
+/// An animation that was playing when the game was paused.
+#[derive(Component)]
+pub struct Frozen;
+
+impl Pause {
+    /// Not while an overlay that leaves the round is shown, escape then counts as any key.
+    pub fn on_escape(input: Res<FrameInput>, game: Res<Game>, mut state: ResMut<State<AppState>>) {
+        if input.pause && !matches!(game.phase(), Phase::End | Phase::Egg) {
+            info!("Pause");
+            if let Err(err) = state.push(AppState::Paused) {
+                warn!("Failed to pause: {:?}", err);
+            }
+        }
+    }
+
+    /// Stops physics and the animations, which also stops the monster from shooting.
+    pub fn freeze(
+        mut commands: Commands,
+        mut rapier_configuration: ResMut<RapierConfiguration>,
+        q_playing: Query<Entity, With<benimator::Play>>,
+    ) {
+        rapier_configuration.physics_pipeline_active = false;
+        for e in q_playing.iter() {
+            commands
+                .entity(e)
+                .remove::<benimator::Play>()
+                .insert(Frozen);
+        }
+    }
+
+    /// Restarts physics unless a hit-stop is still running, which then restarts it itself.
+    pub fn thaw(
+        mut commands: Commands,
+        mut rapier_configuration: ResMut<RapierConfiguration>,
+        effects: Res<CameraEffects>,
+        q_frozen: Query<Entity, With<Frozen>>,
+    ) {
+        rapier_configuration.physics_pipeline_active = !effects.hit_stopped();
+        for e in q_frozen.iter() {
+            commands
+                .entity(e)
+                .remove::<Frozen>()
+                .insert(benimator::Play);
+        }
+    }
+
+    pub fn spawn_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+        Menu::spawn(
+            &mut commands,
+            &asset_server,
+            "Paused",
+            &[
+                ("Resume", MenuAction::Resume, true),
+                ("Restart", MenuAction::Restart, true),
+                ("Settings", MenuAction::Settings, true),
+                ("Main menu", MenuAction::MainMenu, true),
+                ("Quit", MenuAction::Quit, true),
+            ],
+            Some(MenuAction::Resume),
+        );
+    }
+
+    pub fn on_menu(
+        mut commands: Commands,
+        mut ev_menu_action: EventReader<MenuAction>,
+        mut state: ResMut<State<AppState>>,
+        mut ev_app_exit: EventWriter<AppExit>,
+    ) {
+        for action in ev_menu_action.iter() {
+            match action {
+                MenuAction::Resume => {
+                    info!("Resume");
+                    state.pop().unwrap();
+                }
+                MenuAction::Restart => {
+                    commands.insert_resource(Restart {
+                        skip_intro: false,
+                        to_menu: false,
+                    });
+                    state.replace(AppState::Restart).unwrap();
+                }
+                MenuAction::MainMenu => {
+                    commands.insert_resource(Restart {
+                        skip_intro: false,
+                        to_menu: true,
+                    });
+                    state.replace(AppState::Restart).unwrap();
+                }
+                MenuAction::Settings => state.push(AppState::Settings).unwrap(),
+                MenuAction::Quit => ev_app_exit.send(AppExit),
+                _ => {}
+            }
+            // The state changed, ignore anything else sent this frame.
+            break;
+        }
+    }
+}
@@ -1,10 +1,38 @@
 use crate::nalgebra::Vector2;
-use crate::{game, util, PlayerDamaged, PHYSICS_SCALE};
+use crate::{game, util, AppState, AssetProblem, PlayerDamaged, SpriteManifest, Tuning};
 use benimator::SpriteSheetAnimation;
 use bevy::prelude::*;
 use bevy_ase::asset::{Animation, AseFileMap};
 use bevy_rapier2d::prelude::*;
-use std::path::Path;
+
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::Restart).with_system(util::despawn_all::<Player>),
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::Ready)
+                .label("ready_enter")
+                .with_system(Player::spawn.chain(AssetProblem::report)),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Ready)
+                .label("ready_update")
+                .with_system(
+                    PlayerDamaged::handle_event
+                        .label("PlayerDamaged::handle_event")
+                        .before("TargetStatus::changed")
+                        .before("SpawnSlimeBall::handle_event")
+                        .after("SlimeBall::on_contact_started"),
+                )
+                .with_system(Player::on_damaged.after("SlimeBall::on_contact_started"))
+                .with_system(Player::on_phase),
+        )
+        .add_event::<PlayerDamaged>();
+    }
+}
 
 #[derive(Component)]
 pub struct Player {
@@ -13,22 +41,24 @@ pub struct Player {
 }
 
 impl Player {
+    /// Height the player spawns and resets at, in pixels.
+    const SPAWN_Y: f32 = -105.;
+
     pub fn spawn(
         mut commands: Commands,
+        sprite_manifest: Res<SpriteManifest>,
         ase_file_map: Res<AseFileMap>,
         animations: Res<Assets<Animation>>,
         mut sprite_sheet_animations: ResMut<Assets<benimator::SpriteSheetAnimation>>,
-    ) {
-        let asset_map = ase_file_map
-            .get(Path::new("sprites/person_player.aseprite"))
-            .unwrap();
+        tuning: Res<Tuning>,
+    ) -> Result<(), AssetProblem> {
+        let sheet = sprite_manifest.sheet(&ase_file_map, "player")?;
 
-        let (texture_atlas, anim) = util::Animation::get_components(&animations, asset_map, "Idle");
+        let (texture_atlas, anim) = sheet.animation(&animations, "Idle")?;
         let idle_animation =
             util::Animation::from_components(texture_atlas, sprite_sheet_animations.add(anim));
 
-        let (texture_atlas, anim) =
-            util::Animation::get_components(&animations, asset_map, "Slimed");
+        let (texture_atlas, anim) = sheet.animation(&animations, "Slimed")?;
         let slimed_animation =
             util::Animation::from_components(texture_atlas, sprite_sheet_animations.add(anim));
 
@@ -45,14 +75,14 @@ impl Player {
                 slimed_animation,
             })
             .insert_bundle(RigidBodyBundle {
-                position: Vec2::new(0., -105. / PHYSICS_SCALE).into(),
+                position: Vec2::new(0., Self::SPAWN_Y / tuning.physics_scale).into(),
                 ..Default::default()
             })
             .insert_bundle(ColliderBundle {
                 //shape: ColliderShape::cuboid(1.25, 2.2).into(),
                 shape: ColliderShape::capsule(Point::new(0.0, -1.5), Point::new(0.0, 1.2), 0.8)
                     .into(),
-                mass_properties: ColliderMassProps::Density(500.).into(),
+                mass_properties: ColliderMassProps::Density(tuning.player.density).into(),
                 flags: ColliderFlags {
                     solver_groups: InteractionGroups::new(1 << 1, !0),
                     collision_groups: InteractionGroups::new(1 << 1, !0),
@@ -62,6 +92,7 @@ impl Player {
                 ..Default::default()
             })
             .insert(RigidBodyPositionSync::Discrete);
+        Ok(())
     }
 
     pub fn on_damaged(
@@ -90,6 +121,7 @@ impl Player {
             &mut Handle<TextureAtlas>,
             &mut Handle<SpriteSheetAnimation>,
         )>,
+        tuning: Res<Tuning>,
     ) {
         for ev in ev_phase.iter() {
             if let game::Phase::TransDead = ev {
@@ -104,7 +136,7 @@ impl Player {
                 player
                     .idle_animation
                     .apply_animation(&mut texture_atlas, &mut sprite_sheet_animation);
-                rigid_body_position.position.translation.y = -10.5;
+                rigid_body_position.position.translation.y = Self::SPAWN_Y / tuning.physics_scale;
                 rigid_body_position.position.rotation = Rotation::new(0.);
                 rigid_body_velocity.linvel = Vector2::repeat(0.);
                 rigid_body_velocity.angvel = 0.;
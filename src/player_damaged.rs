@@ -1,6 +1,11 @@
-use crate::{BodyPart, BodyPartTextures, Player, SpawnSlimeBall, TargetStatus};
+use crate::{
+    audio::{PlaySound, Sound},
+    particles::{ParticleKind, SpawnParticles},
+    BodyPart, BodyPartTextures, GameRng, Player, SpawnSlimeBall, TargetStatus, Tuning,
+};
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
 
 pub struct PlayerDamaged {
     pub pos: Vector<Real>,
@@ -16,18 +21,27 @@ impl PlayerDamaged {
         mut ev_target_status: EventWriter<TargetStatus>,
         q_rigid_body_velocity: Query<&RigidBodyVelocityComponent, With<Player>>,
         mut ev_spawn_slime_ball: EventWriter<SpawnSlimeBall>,
+        mut ev_play_sound: EventWriter<PlaySound>,
+        mut ev_spawn_particles: EventWriter<SpawnParticles>,
+        mut rng: ResMut<GameRng>,
+        tuning: Res<Tuning>,
     ) {
         for ev in ev_player_damaged.iter() {
             ev_target_status.send(TargetStatus(false));
-            let rand = (ev.pos.x * 1008. - ev.pos.y * 2502.) * std::f32::consts::PI;
+            ev_play_sound.send(PlaySound {
+                sound: Sound::Ouch,
+                volume: tuning.sound.impact_volume(ev.vel.magnitude()),
+            });
+            let texture = rng.0.gen_range(0..body_part_textures.0.len());
+            let rotation = rng.0.gen_range(0.0..std::f32::consts::TAU);
             commands
                 .spawn_bundle(SpriteBundle {
                     transform: Transform::from_xyz(0., 1000., 3.),
-                    texture: body_part_textures.0[(rand as usize) % 6].clone(),
+                    texture: body_part_textures.0[texture].clone(),
                     ..Default::default()
                 })
                 .insert_bundle(RigidBodyBundle {
-                    position: Isometry::new(ev.pos, rand).into(),
+                    position: Isometry::new(ev.pos, rotation).into(),
                     velocity: RigidBodyVelocity {
                         linvel: ev.vel,
                         ..Default::default()
@@ -38,7 +52,7 @@ impl PlayerDamaged {
                 })
                 .insert_bundle(ColliderBundle {
                     shape: ColliderShape::round_cuboid(0.5, 0.8, 0.2).into(),
-                    mass_properties: ColliderMassProps::Density(20.).into(),
+                    mass_properties: ColliderMassProps::Density(tuning.body_part.density).into(),
                     flags: ColliderFlags {
                         solver_groups: InteractionGroups::new(1 << 3, 0b1001),
                         ..Default::default()
@@ -56,6 +70,12 @@ impl PlayerDamaged {
             //split_power *= split_power;
             let new_health = split_power * ev.slime_ball_health as f32;
             if new_health > 10. {
+                ev_play_sound.send(PlaySound::new(Sound::Split));
+                ev_spawn_particles.send(SpawnParticles {
+                    kind: ParticleKind::Burst,
+                    position: ev.pos.into(),
+                    velocity: (-ev.vel).into(),
+                });
                 ev_spawn_slime_ball.send(SpawnSlimeBall {
                     position: Some((ev.pos - (ev.vel).normalize() * 2.0).into()),
                     velocity: Some(-ev.vel),
@@ -1,4 +1,7 @@
-use crate::{util::screen_to_world_pos, MainCamera, TargetStatus};
+use crate::{
+    audio::{PlaySound, Sound},
+    FrameInput, TargetStatus, Tuning,
+};
 use bevy::{math::Vec3Swizzles, prelude::*};
 use bevy_rapier2d::prelude::*;
 
@@ -6,6 +9,15 @@ use bevy_rapier2d::prelude::*;
 pub struct ReadyToJump;
 
 impl ReadyToJump {
+    /// The jump impulse toward a target `offset` pixels away from the player, charged for
+    /// `charge` seconds in [`JumpMode::Charge`](crate::settings::JumpMode::Charge).
+    pub fn impulse(offset: Vec2, charge: Option<f32>, tuning: &Tuning) -> Vec2 {
+        match charge {
+            Some(secs) => offset.normalize_or_zero() * tuning.charge.impulse(secs),
+            None => offset * tuning.jump_impulse,
+        }
+    }
+
     pub fn update(
         mut q_rb: Query<
             (
@@ -15,24 +27,23 @@ impl ReadyToJump {
             ),
             With<ReadyToJump>,
         >,
-        buttons: Res<Input<MouseButton>>,
-        q_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
-        windows: Res<Windows>,
+        input: Res<FrameInput>,
         mut ev_target_status: EventWriter<TargetStatus>,
+        mut ev_play_sound: EventWriter<PlaySound>,
+        tuning: Res<Tuning>,
     ) {
-        if buttons.just_pressed(MouseButton::Left) {
-            let (camera, camera_transform) = q_camera.single();
-            let window = windows.get(camera.window).unwrap();
-            debug_assert_eq!(window.id(), windows.get_primary().unwrap().id());
-
-            if let Some(world_pos) = screen_to_world_pos(camera, camera_transform, window) {
-                for (mut velocity, mass_props, transform) in q_rb.iter_mut() {
-                    let impulse = (world_pos - transform.translation.xy()) * 300.0;
-                    velocity.apply_impulse(mass_props, impulse.into());
-                    info!("THIS IS SOME IMPULSE: {}", impulse);
-                }
-                ev_target_status.send(TargetStatus(false));
-            }
+        // A press starts charging in `JumpMode::Charge`, the jump waits for the release.
+        let (world_pos, charge) = match (input.release, input.charge, input.click) {
+            (Some(world_pos), Some(secs), _) => (world_pos, Some(secs)),
+            (None, None, Some(world_pos)) => (world_pos, None),
+            _ => return,
+        };
+        let world_pos = Vec2::from(world_pos);
+        for (mut velocity, mass_props, transform) in q_rb.iter_mut() {
+            let impulse = Self::impulse(world_pos - transform.translation.xy(), charge, &tuning);
+            velocity.apply_impulse(mass_props, impulse.into());
+            ev_play_sound.send(PlaySound::new(Sound::Jump));
         }
+        ev_target_status.send(TargetStatus(false));
     }
 }
@@ -0,0 +1,280 @@
+use crate::{
+    frame_input::{FrameInput, SimulationFrame},
+    game, util, AppState, AssetProblem, AssetProblems, Game, RngSeed, Score, SpawnSlimeBall,
+};
+use bevy::{app::AppExit, ecs::schedule::ShouldRun, input::InputSystem, prelude::*};
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Records gameplay input to a file with `--record <path>` and plays it back with
+/// `--replay <path>` instead of live input.
+///
+/// Both modes switch rapier to a fixed timestep so the physics does not depend on the frame rate.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let mode = match app.world.remove_resource::<ReplayMode>() {
+            Some(mode) => mode,
+            None => ReplayMode::from_args().unwrap_or_else(|problem| {
+                // Shown once loading is done, instead of playing without the replay.
+                app.init_resource::<AssetProblems>();
+                app.world
+                    .get_resource_mut::<AssetProblems>()
+                    .unwrap()
+                    .0
+                    .push(problem);
+                ReplayMode::Off
+            }),
+        };
+        match &mode {
+            ReplayMode::Off => {}
+            ReplayMode::Record { path, .. } => info!("Recording replay to {:?}", path),
+            ReplayMode::Playback { replay, .. } => {
+                info!("Playing back replay with seed {}", replay.seed);
+                app.insert_resource(RngSeed(replay.seed));
+            }
+        }
+        if !matches!(mode, ReplayMode::Off) {
+            if let Some(mut config) = app.world.get_resource_mut::<RapierConfiguration>() {
+                config.timestep_mode = TimestepMode::FixedTimestep;
+            }
+        }
+
+        app.insert_resource(mode)
+            .init_resource::<FrameInput>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::Menu)
+                    .label("menu_enter")
+                    .with_system(SimulationFrame::start),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                FrameInput::read_live
+                    .with_run_criteria(ReplayMode::live_input)
+                    .after(InputSystem)
+                    .after("Aim::update"),
+            )
+            .add_system_to_stage(CoreStage::PreUpdate, ReplayMode::play.after(InputSystem))
+            .add_system_to_stage(
+                CoreStage::Last,
+                ReplayMode::record.label("ReplayMode::record"),
+            )
+            .add_system_to_stage(
+                CoreStage::Last,
+                ReplayMode::save
+                    .label("ReplayMode::save")
+                    .after("ReplayMode::record"),
+            )
+            .add_system_to_stage(
+                CoreStage::Last,
+                SimulationFrame::advance.after("ReplayMode::save"),
+            );
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct ReplayFile {
+    pub seed: u64,
+    /// Every frame that had any input.
+    pub inputs: Vec<(u64, FrameInput)>,
+    /// Frame and health of every slime ball the monster shot. The monster's animations run on
+    /// wall-clock time, so its shots are part of the recording rather than re-derived.
+    pub monster_shots: Vec<(u64, i32)>,
+    pub last_frame: u64,
+    pub score: u32,
+    pub phase: Option<game::Phase>,
+}
+
+pub enum ReplayMode {
+    Off,
+    Record {
+        path: PathBuf,
+        replay: ReplayFile,
+    },
+    Playback {
+        replay: ReplayFile,
+        next_input: usize,
+        next_shot: usize,
+        finished: bool,
+    },
+}
+
+impl ReplayMode {
+    /// Ten seconds at 60 frames per second.
+    pub const FLUSH_FRAMES: u64 = 600;
+
+    /// Fails if the file passed to `--replay` cannot be read, the details are logged.
+    pub fn from_args() -> Result<Self, AssetProblem> {
+        let mode = if let Some(path) = util::arg_value("replay") {
+            let replay = std::fs::read_to_string(&path).map_err(|err| {
+                error!("Failed to read replay {:?}: {}", path, err);
+                AssetProblem::MissingFile { path: path.clone() }
+            })?;
+            let replay = ron::from_str(&replay).map_err(|err| {
+                error!("Failed to parse replay {:?}: {}", path, err);
+                AssetProblem::Invalid { path: path.clone() }
+            })?;
+            ReplayMode::Playback {
+                replay,
+                next_input: 0,
+                next_shot: 0,
+                finished: false,
+            }
+        } else if let Some(path) = util::arg_value("record") {
+            ReplayMode::Record {
+                path: path.into(),
+                replay: ReplayFile::default(),
+            }
+        } else {
+            ReplayMode::Off
+        };
+        Ok(mode)
+    }
+
+    pub fn is_playback(&self) -> bool {
+        matches!(
+            self,
+            ReplayMode::Playback {
+                finished: false,
+                ..
+            }
+        )
+    }
+
+    pub fn live_input(mode: Res<ReplayMode>) -> ShouldRun {
+        if mode.is_playback() {
+            ShouldRun::No
+        } else {
+            ShouldRun::Yes
+        }
+    }
+
+    pub fn play(
+        mut mode: ResMut<ReplayMode>,
+        mut input: ResMut<FrameInput>,
+        frame: Option<Res<SimulationFrame>>,
+        mut ev_spawn_slime_ball: EventWriter<SpawnSlimeBall>,
+        game: Option<Res<Game>>,
+        q_score: Query<&Score>,
+    ) {
+        if let ReplayMode::Playback {
+            replay,
+            next_input,
+            next_shot,
+            finished: finished @ false,
+        } = &mut *mode
+        {
+            let frame = if let Some(frame) = frame {
+                frame.0
+            } else {
+                *input = FrameInput::default();
+                return;
+            };
+
+            *input = match replay.inputs.get(*next_input) {
+                Some((input_frame, recorded)) if *input_frame == frame => {
+                    *next_input += 1;
+                    recorded.clone()
+                }
+                _ => FrameInput::default(),
+            };
+
+            while let Some((shot_frame, health)) = replay.monster_shots.get(*next_shot) {
+                if *shot_frame != frame {
+                    break;
+                }
+                ev_spawn_slime_ball.send(SpawnSlimeBall {
+                    position: None,
+                    velocity: None,
+                    health: *health,
+                });
+                *next_shot += 1;
+            }
+
+            if frame >= replay.last_frame {
+                *finished = true;
+                let score = q_score.get_single().map(|score| score.0).ok();
+                let phase = game.map(|game| game.phase().clone());
+                if score == Some(replay.score) && phase == replay.phase {
+                    info!("Replay finished: score {:?}, phase {:?}", score, phase);
+                } else {
+                    warn!(
+                        "Replay diverged: ended with score {:?}, phase {:?} but recorded score {}, phase {:?}",
+                        score, phase, replay.score, replay.phase
+                    );
+                }
+            }
+        }
+    }
+
+    pub fn record(
+        mut mode: ResMut<ReplayMode>,
+        input: Res<FrameInput>,
+        frame: Option<Res<SimulationFrame>>,
+        mut ev_spawn_slime_ball: EventReader<SpawnSlimeBall>,
+    ) {
+        if let (ReplayMode::Record { replay, .. }, Some(frame)) = (&mut *mode, frame) {
+            if !input.is_empty() {
+                replay.inputs.push((frame.0, input.clone()));
+            }
+            // Shots sent now are spawned next frame.
+            for ev in ev_spawn_slime_ball.iter() {
+                if ev.position.is_none() {
+                    replay.monster_shots.push((frame.0 + 1, ev.health));
+                }
+            }
+        }
+    }
+
+    /// Keeps the end of the recording up to date every frame and writes it on [`AppExit`] and
+    /// every [`ReplayMode::FLUSH_FRAMES`] frames, so a crash does not lose the whole run.
+    pub fn save(
+        mut mode: ResMut<ReplayMode>,
+        mut ev_app_exit: EventReader<AppExit>,
+        seed: Res<RngSeed>,
+        frame: Option<Res<SimulationFrame>>,
+        game: Option<Res<Game>>,
+        q_score: Query<&Score>,
+    ) {
+        let exiting = ev_app_exit.iter().next().is_some();
+        if let ReplayMode::Record { path, replay } = &mut *mode {
+            replay.seed = seed.0;
+            replay.last_frame = frame.as_ref().map_or(0, |frame| frame.0);
+            replay.score = q_score.get_single().map_or(0, |score| score.0);
+            replay.phase = game.map(|game| game.phase().clone());
+
+            if exiting {
+                match replay.write(path) {
+                    Ok(()) => info!("Saved replay to {:?}", path),
+                    Err(err) => error!("Failed to save replay to {:?}: {}", path, err),
+                }
+            } else if frame.is_some() && replay.last_frame % Self::FLUSH_FRAMES == 0 {
+                if let Err(err) = replay.write(path) {
+                    warn!("Failed to save replay to {:?}: {}", path, err);
+                }
+            }
+        }
+    }
+}
+
+/// Writes the recording when the app goes away without an [`AppExit`], e.g. on a panic.
+impl Drop for ReplayMode {
+    fn drop(&mut self) {
+        if let ReplayMode::Record { path, replay } = self {
+            match replay.write(path) {
+                Ok(()) => info!("Saved replay to {:?}", path),
+                Err(err) => error!("Failed to save replay to {:?}: {}", path, err),
+            }
+        }
+    }
+}
+
+impl ReplayFile {
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let serialized = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new())
+            .map_err(|err| err.to_string())?;
+        std::fs::write(path, serialized).map_err(|err| err.to_string())
+    }
+}
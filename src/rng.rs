@@ -0,0 +1,61 @@
+use crate::util;
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+
+/// Every gameplay system draws its randomness from here, so a run is fully determined by its
+/// [`RngSeed`] and its inputs.
+pub struct GameRng(pub ChaCha8Rng);
+
+#[derive(Clone, Copy, Debug)]
+pub struct RngSeed(pub u64);
+
+/// Optional `ad_opt.ron` next to the executable's working directory.
+#[derive(Deserialize, Default)]
+struct RunConfig {
+    seed: Option<u64>,
+}
+
+impl RngSeed {
+    pub const CONFIG_PATH: &'static str = "ad_opt.ron";
+
+    /// `--seed <n>` wins over the `seed` in [`RngSeed::CONFIG_PATH`]. Without either, a random
+    /// seed is picked and logged so the run can still be reproduced.
+    pub fn from_env() -> Self {
+        if let Some(seed) = util::arg_value("seed") {
+            match seed.parse() {
+                Ok(seed) => return RngSeed(seed),
+                Err(err) => warn!("Ignoring invalid --seed {:?}: {}", seed, err),
+            }
+        }
+
+        let config = match std::fs::read_to_string(Self::CONFIG_PATH) {
+            Ok(config) => ron::from_str(&config).unwrap_or_else(|err| {
+                warn!("Ignoring invalid {}: {}", Self::CONFIG_PATH, err);
+                RunConfig::default()
+            }),
+            Err(_) => RunConfig::default(),
+        };
+
+        RngSeed(config.seed.unwrap_or_else(rand::random))
+    }
+}
+
+/// Inserts [`GameRng`], seeded from an already inserted [`RngSeed`] or [`RngSeed::from_env`].
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = match app.world.get_resource::<RngSeed>() {
+            Some(seed) => *seed,
+            None => {
+                let seed = RngSeed::from_env();
+                app.insert_resource(seed);
+                seed
+            }
+        };
+        info!("RNG seed: {}", seed.0);
+        app.insert_resource(GameRng(ChaCha8Rng::seed_from_u64(seed.0)));
+    }
+}
@@ -1,12 +1,37 @@
-use crate::{game, PlayerDamaged, HALF_HEIGHT, HALF_WIDTH};
+use crate::{game, util, AppState, PlayerDamaged, FONT_PATH, HALF_HEIGHT, HALF_WIDTH};
 use bevy::prelude::*;
 
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::Restart).with_system(util::despawn_all::<Score>),
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::Ready)
+                .label("ready_enter")
+                .with_system(Score::spawn),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Ready)
+                .label("ready_update")
+                .with_system(Score::on_player_damaged.after("SlimeBall::on_contact_started"))
+                .with_system(
+                    Score::on_end
+                        .after("BodyPart::win_check")
+                        .before("Game::update"),
+                ),
+        );
+    }
+}
+
 #[derive(Component)]
 pub struct Score(pub u32);
 
 impl Score {
     pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>) {
-        let font = asset_server.load("fonts/iosevka-extendedsemibold.ttf");
+        let font = asset_server.load(FONT_PATH);
         let text_style = TextStyle {
             font,
             font_size: 20.0,
@@ -0,0 +1,439 @@
+use crate::{
+    actions::{Action, ActionBinding, Bindings, Devices},
+    menu::{Menu, MenuAction, MenuItem},
+    AppState,
+};
+use bevy::{prelude::*, window::WindowMode};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+/// User options, kept in `settings.ron` in the user's config directory.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub resolution: (u32, u32),
+    pub fullscreen: bool,
+    pub vsync: bool,
+    /// Volumes from 0 to 1.
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub bindings: Bindings,
+    pub cursor: CursorMode,
+    pub gamepad_aim: GamepadAim,
+    pub jump_mode: JumpMode,
+    /// Shows the arc of the next jump.
+    pub trajectory_preview: bool,
+    pub slime_warnings: SlimeWarnings,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CursorMode {
+    /// The target cursor sprite.
+    Game,
+    /// The operating system's cursor.
+    System,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GamepadAim {
+    /// The right stick moves the target.
+    Cursor,
+    /// The right stick points the jump from the player.
+    Direction,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JumpMode {
+    /// Pressing jump jumps harder the farther away the target is.
+    Distance,
+    /// Holding jump charges it, releasing jumps toward the target.
+    Charge,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlimeWarnings {
+    Off,
+    /// Marks where the next shot slime ball enters.
+    Entry,
+    /// Also shows where every slime ball bounces next.
+    Paths,
+}
+
+/// A line of the settings screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    Resolution,
+    Fullscreen,
+    VSync,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Cursor,
+    GamepadAim,
+    JumpMode,
+    TrajectoryPreview,
+    SlimeWarnings,
+    Binding(Action),
+}
+
+/// The action waiting for a key or button press on the settings screen.
+#[derive(Default)]
+pub struct Rebinding(pub Option<Action>);
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            resolution: (1280, 720),
+            fullscreen: false,
+            vsync: true,
+            master_volume: 1.,
+            music_volume: 0.7,
+            sfx_volume: 1.,
+            bindings: Bindings::default(),
+            cursor: CursorMode::Game,
+            gamepad_aim: GamepadAim::Cursor,
+            jump_mode: JumpMode::Distance,
+            trajectory_preview: true,
+            slime_warnings: SlimeWarnings::Entry,
+        }
+    }
+}
+
+/// Inserts [`Settings`], from an already inserted resource or [`Settings::load`], and adds the
+/// settings screen.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        if app.world.get_resource::<Settings>().is_none() {
+            app.insert_resource(Settings::load());
+        }
+        app.init_resource::<Rebinding>()
+            .add_system_set(SystemSet::on_enter(AppState::Settings).with_system(Settings::spawn))
+            .add_system_set(
+                SystemSet::on_update(AppState::Settings)
+                    .with_system(
+                        Settings::on_menu
+                            .label("Settings::on_menu")
+                            .after("Menu::navigate"),
+                    )
+                    // Before `on_menu`, so the press that started rebinding is not bound.
+                    .with_system(
+                        Settings::rebind
+                            .after("Menu::navigate")
+                            .before("Settings::on_menu"),
+                    )
+                    .with_system(Settings::refresh.after("Menu::navigate"))
+                    .with_system(Settings::apply),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Settings)
+                    .with_system(Menu::despawn)
+                    .with_system(Settings::on_exit),
+            );
+    }
+}
+
+impl Settings {
+    const RESOLUTIONS: &'static [(u32, u32)] = &[
+        (960, 540),
+        (1280, 720),
+        (1600, 900),
+        (1920, 1080),
+        (2560, 1440),
+    ];
+    const ITEMS: &'static [Setting] = &[
+        Setting::Resolution,
+        Setting::Fullscreen,
+        Setting::VSync,
+        Setting::MasterVolume,
+        Setting::MusicVolume,
+        Setting::SfxVolume,
+        Setting::Cursor,
+        Setting::GamepadAim,
+        Setting::JumpMode,
+        Setting::TrajectoryPreview,
+        Setting::SlimeWarnings,
+        Setting::Binding(Action::Jump),
+        Setting::Binding(Action::Confirm),
+        Setting::Binding(Action::Pause),
+        Setting::Binding(Action::Retry),
+        Setting::Binding(Action::Up),
+        Setting::Binding(Action::Down),
+    ];
+
+    /// `None` where there is no config directory, e.g. on the web.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("ad_opt").join("settings.ron"))
+    }
+
+    /// Falls back to the defaults if the file is missing or invalid.
+    pub fn load() -> Self {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return Settings::default(),
+        };
+        match fs::read_to_string(&path) {
+            Ok(settings) => ron::from_str(&settings).unwrap_or_else(|err| {
+                warn!("Ignoring invalid {}: {}", path.display(), err);
+                Settings::default()
+            }),
+            Err(err) => {
+                if path.exists() {
+                    warn!("Could not read {}: {}", path.display(), err);
+                }
+                Settings::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return,
+        };
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|settings| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+                }
+                fs::write(&path, settings).map_err(|err| err.to_string())
+            });
+        match result {
+            Ok(()) => info!("Saved settings to {}", path.display()),
+            Err(err) => warn!("Could not save settings to {}: {}", path.display(), err),
+        }
+    }
+
+    pub fn window_descriptor(&self) -> WindowDescriptor {
+        WindowDescriptor {
+            title: String::from("Ad_Opt"),
+            width: self.resolution.0 as f32,
+            height: self.resolution.1 as f32,
+            vsync: self.vsync,
+            mode: self.window_mode(),
+            cursor_visible: self.cursor == CursorMode::System,
+            ..Default::default()
+        }
+    }
+
+    fn window_mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::BorderlessFullscreen
+        } else {
+            WindowMode::Windowed
+        }
+    }
+
+    fn label(&self, setting: Setting, rebinding: Option<Action>) -> String {
+        let on_off = |on| if on { "on" } else { "off" };
+        let percent = |volume: f32| format!("{:.0}%", volume * 100.);
+        match setting {
+            Setting::Resolution => {
+                format!("Resolution: {}x{}", self.resolution.0, self.resolution.1)
+            }
+            Setting::Fullscreen => format!("Fullscreen: {}", on_off(self.fullscreen)),
+            Setting::VSync => format!("VSync: {}", on_off(self.vsync)),
+            Setting::MasterVolume => format!("Master volume: {}", percent(self.master_volume)),
+            Setting::MusicVolume => format!("Music volume: {}", percent(self.music_volume)),
+            Setting::SfxVolume => format!("Effects volume: {}", percent(self.sfx_volume)),
+            Setting::Cursor => format!("Cursor: {:?}", self.cursor),
+            Setting::GamepadAim => format!("Gamepad aim: {:?}", self.gamepad_aim),
+            Setting::JumpMode => format!("Jump: {:?}", self.jump_mode),
+            Setting::TrajectoryPreview => {
+                format!("Jump preview: {}", on_off(self.trajectory_preview))
+            }
+            Setting::SlimeWarnings => format!("Slime warnings: {:?}", self.slime_warnings),
+            Setting::Binding(action) if rebinding == Some(action) => {
+                format!("{:?}: press a key or button", action)
+            }
+            Setting::Binding(action) => {
+                format!("{:?}: {}", action, self.bindings.get(action).describe())
+            }
+        }
+    }
+
+    /// Steps `setting` to its next value.
+    fn cycle(&mut self, setting: Setting) {
+        let next_volume = |volume: f32| {
+            if volume >= 0.95 {
+                0.
+            } else {
+                ((volume * 10.).round() + 1.) / 10.
+            }
+        };
+        match setting {
+            Setting::Resolution => {
+                let next = Self::RESOLUTIONS
+                    .iter()
+                    .position(|resolution| *resolution == self.resolution)
+                    .map_or(0, |i| (i + 1) % Self::RESOLUTIONS.len());
+                self.resolution = Self::RESOLUTIONS[next];
+            }
+            Setting::Fullscreen => self.fullscreen = !self.fullscreen,
+            Setting::VSync => self.vsync = !self.vsync,
+            Setting::MasterVolume => self.master_volume = next_volume(self.master_volume),
+            Setting::MusicVolume => self.music_volume = next_volume(self.music_volume),
+            Setting::SfxVolume => self.sfx_volume = next_volume(self.sfx_volume),
+            Setting::Cursor => {
+                self.cursor = match self.cursor {
+                    CursorMode::Game => CursorMode::System,
+                    CursorMode::System => CursorMode::Game,
+                }
+            }
+            Setting::GamepadAim => {
+                self.gamepad_aim = match self.gamepad_aim {
+                    GamepadAim::Cursor => GamepadAim::Direction,
+                    GamepadAim::Direction => GamepadAim::Cursor,
+                }
+            }
+            Setting::JumpMode => {
+                self.jump_mode = match self.jump_mode {
+                    JumpMode::Distance => JumpMode::Charge,
+                    JumpMode::Charge => JumpMode::Distance,
+                }
+            }
+            Setting::TrajectoryPreview => self.trajectory_preview = !self.trajectory_preview,
+            Setting::SlimeWarnings => {
+                self.slime_warnings = match self.slime_warnings {
+                    SlimeWarnings::Off => SlimeWarnings::Entry,
+                    SlimeWarnings::Entry => SlimeWarnings::Paths,
+                    SlimeWarnings::Paths => SlimeWarnings::Off,
+                }
+            }
+            // Waits for a key or button in `rebind`.
+            Setting::Binding(_) => {}
+        }
+    }
+
+    pub fn spawn(mut commands: Commands, asset_server: Res<AssetServer>, settings: Res<Settings>) {
+        let labels: Vec<_> = Self::ITEMS
+            .iter()
+            .map(|setting| settings.label(*setting, None))
+            .collect();
+        let mut items: Vec<_> = Self::ITEMS
+            .iter()
+            .zip(&labels)
+            .map(|(setting, label)| (label.as_str(), MenuAction::Setting(*setting), true))
+            .collect();
+        items.push(("Back", MenuAction::Back, true));
+        Menu::spawn(
+            &mut commands,
+            &asset_server,
+            "Settings",
+            &items,
+            Some(MenuAction::Back),
+        );
+    }
+
+    pub fn on_menu(
+        mut ev_menu_action: EventReader<MenuAction>,
+        mut settings: ResMut<Settings>,
+        mut rebinding: ResMut<Rebinding>,
+        mut q_menu: Query<&mut Menu>,
+        mut state: ResMut<State<AppState>>,
+    ) {
+        for action in ev_menu_action.iter() {
+            match action {
+                MenuAction::Setting(Setting::Binding(action)) => {
+                    rebinding.0 = Some(*action);
+                    for mut menu in q_menu.iter_mut() {
+                        menu.locked = true;
+                    }
+                }
+                MenuAction::Setting(setting) => settings.cycle(*setting),
+                MenuAction::Back => {
+                    state.pop().unwrap();
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Binds the next pressed key or button while [`Rebinding`] is set. Only the binding of the
+    /// device it came from changes, the others stay. Keys and buttons already bound to another
+    /// action are ignored, so no press does two things.
+    pub fn rebind(
+        mut settings: ResMut<Settings>,
+        mut rebinding: ResMut<Rebinding>,
+        keys: Res<Input<KeyCode>>,
+        buttons: Res<Input<MouseButton>>,
+        gamepad_buttons: Res<Input<GamepadButton>>,
+        mut q_menu: Query<&mut Menu>,
+    ) {
+        let action = match rebinding.0 {
+            Some(action) => action,
+            None => return,
+        };
+        let devices = Devices {
+            keys: &keys,
+            mouse: &buttons,
+            gamepad_buttons: &gamepad_buttons,
+        };
+        let press = match ActionBinding::from_press(&devices) {
+            Some(press) => press,
+            None => return,
+        };
+        if let Some(other) = settings.bindings.taken(action, &press) {
+            warn!("{} is already bound to {:?}", press.describe(), other);
+            return;
+        }
+        settings.bindings.get_mut(action).replace(&press);
+        rebinding.0 = None;
+        for mut menu in q_menu.iter_mut() {
+            menu.locked = false;
+        }
+    }
+
+    pub fn refresh(
+        settings: Res<Settings>,
+        rebinding: Res<Rebinding>,
+        mut q_item: Query<(&MenuItem, &mut Text)>,
+    ) {
+        if !settings.is_changed() && !rebinding.is_changed() {
+            return;
+        }
+        for (item, mut text) in q_item.iter_mut() {
+            if let MenuAction::Setting(setting) = item.action {
+                text.sections[0].value = settings.label(setting, rebinding.0);
+            }
+        }
+    }
+
+    /// Only sets the window fields that changed since they were last applied, so e.g. a volume
+    /// change does not undo a resized window.
+    pub fn apply(
+        settings: Res<Settings>,
+        mut windows: ResMut<Windows>,
+        mut applied: Local<Option<((u32, u32), bool, bool)>>,
+    ) {
+        let window_fields = (settings.resolution, settings.fullscreen, settings.vsync);
+        // The window was created with the settings loaded at startup, which only change here.
+        let (resolution, fullscreen, vsync) = *applied.get_or_insert(window_fields);
+        if !settings.is_changed() {
+            return;
+        }
+        // There is no window when running headless.
+        if let Some(window) = windows.get_primary_mut() {
+            if resolution != settings.resolution {
+                window.set_resolution(settings.resolution.0 as f32, settings.resolution.1 as f32);
+            }
+            if fullscreen != settings.fullscreen {
+                window.set_mode(settings.window_mode());
+            }
+            if vsync != settings.vsync {
+                window.set_vsync(settings.vsync);
+            }
+            window.set_cursor_visibility(settings.cursor == CursorMode::System);
+        }
+        *applied = Some(window_fields);
+    }
+
+    pub fn on_exit(settings: Res<Settings>, mut rebinding: ResMut<Rebinding>) {
+        rebinding.0 = None;
+        settings.save();
+    }
+}
@@ -1,13 +1,43 @@
 use crate::nalgebra::{Isometry2, Point2};
 use crate::{
-    nalgebra::Vector2, util, util::DespawnEntity, Player, PlayerDamaged, HALF_HEIGHT, HALF_WIDTH,
-    PHYSICS_SCALE,
+    audio::{PlaySound, Sound},
+    nalgebra::Vector2,
+    particles::{ParticleKind, SpawnParticles},
+    util,
+    util::DespawnEntity,
+    AppState, AssetProblem, GameRng, Player, PlayerDamaged, SpriteManifest, Tuning, HALF_HEIGHT,
+    HALF_WIDTH,
 };
 use benimator::SpriteSheetAnimation;
 use bevy::prelude::*;
 use bevy_ase::asset::{Animation, AseFileMap};
 use bevy_rapier2d::prelude::*;
-use std::path::Path;
+use rand::Rng;
+
+pub struct SlimeBallPlugin;
+
+impl Plugin for SlimeBallPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::Restart).with_system(util::despawn_all::<SlimeBall>),
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::Ready)
+                .label("ready_enter")
+                .with_system(SlimeBallBundle::setup.chain(AssetProblem::report)),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Ready)
+                .label("ready_update")
+                .with_system(SlimeBall::update.after("SpawnSlimeBall::handle_event"))
+                .with_system(SlimeBall::on_contact_stopped)
+                .with_system(SlimeBall::on_contact_started.label("SlimeBall::on_contact_started"))
+                .with_system(SpawnSlimeBall::handle_event.label("SpawnSlimeBall::handle_event")),
+        )
+        .init_resource::<NextShot>()
+        .add_event::<SpawnSlimeBall>();
+    }
+}
 
 #[derive(Component, Clone)]
 pub struct SlimeBall {
@@ -22,21 +52,45 @@ pub struct SpawnSlimeBall {
     pub health: i32,
 }
 
+/// The velocity of the monster's next shot, picked a shot ahead so
+/// [`EntryMarker`](crate::telegraph::EntryMarker) can show where it comes in.
+#[derive(Default)]
+pub struct NextShot(pub Option<Vector2<f32>>);
+
 impl SpawnSlimeBall {
+    /// Where the balls the monster shoots enter, above the top wall.
+    pub fn entry_position(tuning: &Tuning) -> Isometry2<f32> {
+        Isometry::translation(0., HALF_HEIGHT * 1.2 / tuning.physics_scale)
+    }
+
+    /// Where a shot fired with `velocity` falls through the top wall, in physics units.
+    pub fn entry_x(velocity: Vector2<f32>, tuning: &Tuning) -> f32 {
+        let start = Self::entry_position(tuning).translation;
+        let drop = HALF_HEIGHT / tuning.physics_scale - start.y;
+        // The first time the ball has fallen `drop` under gravity.
+        let (vy, gravity) = (velocity.y, tuning.gravity);
+        let secs = if gravity == 0. {
+            drop / vy
+        } else {
+            (-vy - (vy * vy + 2. * gravity * drop).max(0.).sqrt()) / gravity
+        };
+        start.x + velocity.x * secs
+    }
+
     pub fn handle_event(
         mut commands: Commands,
         slime_ball_bundle: Res<SlimeBallBundle>,
         mut ev_spawn_slime_ball: EventReader<SpawnSlimeBall>,
-        time: Res<Time>,
+        mut rng: ResMut<GameRng>,
+        mut next_shot: ResMut<NextShot>,
+        tuning: Res<Tuning>,
     ) {
-        let rand = time.time_since_startup().as_secs_f32();
+        let mut roll = || Vector::new(rng.0.gen_range(-0.1..=0.1), -0.1);
         for ev in ev_spawn_slime_ball.iter() {
-            let position = ev
-                .position
-                .unwrap_or_else(|| Isometry::translation(0., HALF_HEIGHT * 1.2 / PHYSICS_SCALE));
+            let position = ev.position.unwrap_or_else(|| Self::entry_position(&tuning));
             let velocity = ev
                 .velocity
-                .unwrap_or_else(|| Vector::new(rand.sin() * 0.1, -0.1));
+                .unwrap_or_else(|| next_shot.0.take().unwrap_or_else(&mut roll));
 
             let mut slime_ball_bundle = slime_ball_bundle.clone();
             slime_ball_bundle.slime_ball.health = ev.health;
@@ -53,7 +107,7 @@ impl SpawnSlimeBall {
                     ccd: RigidBodyCcd {
                         ccd_enabled: true,
                         ccd_thickness: 0.1,
-                        ccd_max_dist: 0.6,
+                        ccd_max_dist: tuning.slime_ball.radius,
                         ..Default::default()
                     }
                     .into(),
@@ -65,8 +119,8 @@ impl SpawnSlimeBall {
                     ..Default::default()
                 })
                 .insert_bundle(ColliderBundle {
-                    shape: ColliderShape::ball(0.6).into(),
-                    mass_properties: ColliderMassProps::Density(40.).into(),
+                    shape: ColliderShape::ball(tuning.slime_ball.radius).into(),
+                    mass_properties: ColliderMassProps::Density(tuning.slime_ball.density).into(),
                     flags: ColliderFlags {
                         active_events: ActiveEvents::CONTACT_EVENTS,
                         solver_groups: InteractionGroups::new(1 << 2, !(1 << 0)),
@@ -75,7 +129,7 @@ impl SpawnSlimeBall {
                     .into(),
                     material: ColliderMaterial {
                         friction: 0.0,
-                        restitution: 1.0,
+                        restitution: tuning.slime_ball.restitution,
                         friction_combine_rule: CoefficientCombineRule::Min,
                         restitution_combine_rule: CoefficientCombineRule::Max,
                     }
@@ -84,6 +138,9 @@ impl SpawnSlimeBall {
                 })
                 .insert(RigidBodyPositionSync::Discrete);
         }
+        if next_shot.0.is_none() {
+            next_shot.0 = Some(roll());
+        }
     }
 }
 
@@ -110,11 +167,20 @@ impl SlimeBall {
     }
 
     pub fn on_contact_started(
-        mut q_slime_ball: Query<(Entity, &mut SlimeBall, &RigidBodyVelocityComponent)>,
+        mut q_slime_ball: Query<(
+            Entity,
+            &mut SlimeBall,
+            &RigidBodyVelocityComponent,
+            &RigidBodyPositionComponent,
+        )>,
         mut contact_events: EventReader<ContactEvent>,
         q_player: Query<&RigidBodyPositionComponent, With<Player>>,
+        narrow_phase: Res<NarrowPhase>,
         mut ev_despawn_entity: EventWriter<util::DespawnEntity>,
         mut ev_damaged_player: EventWriter<PlayerDamaged>,
+        mut ev_play_sound: EventWriter<PlaySound>,
+        mut ev_spawn_particles: EventWriter<SpawnParticles>,
+        tuning: Res<Tuning>,
     ) {
         for contact_event in contact_events.iter() {
             if let ContactEvent::Started(a, b) = contact_event {
@@ -126,21 +192,57 @@ impl SlimeBall {
                     None
                 };
 
-                if let Some((other_collider, (entity, mut slime_ball, rigid_body_velocity))) = info
+                if let Some((
+                    other_collider,
+                    (entity, mut slime_ball, rigid_body_velocity, rigid_body_position),
+                )) = info
                 {
+                    let ball_position = rigid_body_position.position.translation.vector;
+                    let contact_point = narrow_phase
+                        .contact_pair(*a, *b)
+                        .and_then(|pair| {
+                            pair.manifolds
+                                .iter()
+                                .flat_map(|manifold| &manifold.data.solver_contacts)
+                                .next()
+                        })
+                        .map_or(ball_position, |contact| contact.point.coords);
+                    ev_spawn_particles.send(SpawnParticles {
+                        kind: ParticleKind::Spray,
+                        position: contact_point.into(),
+                        velocity: rigid_body_velocity.linvel.into(),
+                    });
+
+                    let sound = if q_player.get(other_collider.entity()).is_ok() {
+                        Sound::Hit
+                    } else {
+                        Sound::Bounce
+                    };
+                    ev_play_sound.send(PlaySound {
+                        sound,
+                        volume: tuning
+                            .sound
+                            .impact_volume(rigid_body_velocity.linvel.magnitude()),
+                    });
+
                     if !slime_ball.invincible {
                         if let Ok(rigid_body_position) = q_player.get(other_collider.entity()) {
-                            slime_ball.health -= 25;
+                            slime_ball.health -= tuning.slime_ball.player_damage;
                             ev_damaged_player.send(PlayerDamaged {
                                 pos: rigid_body_position.position.translation.vector,
                                 vel: rigid_body_velocity.linvel,
                                 slime_ball_health: slime_ball.health,
                             })
                         } else {
-                            slime_ball.health -= 15;
+                            slime_ball.health -= tuning.slime_ball.wall_damage;
                         }
 
                         if slime_ball.health <= 0 {
+                            ev_spawn_particles.send(SpawnParticles {
+                                kind: ParticleKind::Splat,
+                                position: ball_position.into(),
+                                velocity: rigid_body_velocity.linvel.into(),
+                            });
                             ev_despawn_entity.send(DespawnEntity(entity));
                         }
                     }
@@ -157,6 +259,7 @@ impl SlimeBall {
             &RigidBodyVelocityComponent,
         )>,
         mut ev_despawn_entity: EventWriter<DespawnEntity>,
+        tuning: Res<Tuning>,
     ) {
         for (entity, mut slime_ball, mut rigid_body_position, rigid_body_velocity) in
             q_slime_ball.iter_mut()
@@ -173,7 +276,7 @@ impl SlimeBall {
                 &rigid_body_position.position.translation.vector.into(),
                 &Point2::new(0., 0.),
             );
-            if distance > HALF_WIDTH / PHYSICS_SCALE {
+            if distance > HALF_WIDTH / tuning.physics_scale {
                 ev_despawn_entity.send(DespawnEntity(entity));
             }
         }
@@ -192,15 +295,14 @@ pub struct SlimeBallBundle {
 impl SlimeBallBundle {
     pub fn setup(
         mut commands: Commands,
+        sprite_manifest: Res<SpriteManifest>,
         ase_file_map: Res<AseFileMap>,
         animations: Res<Assets<Animation>>,
         mut sprite_sheet_animations: ResMut<Assets<benimator::SpriteSheetAnimation>>,
-    ) {
-        let asset_map = ase_file_map
-            .get(Path::new("sprites/slime_ball.aseprite"))
-            .unwrap();
+    ) -> Result<(), AssetProblem> {
+        let sheet = sprite_manifest.sheet(&ase_file_map, "slime_ball")?;
 
-        let (texture_atlas, anim) = util::Animation::get_components(&animations, asset_map, "Idle");
+        let (texture_atlas, anim) = sheet.animation(&animations, "Idle")?;
         let idle_animation =
             util::Animation::from_components(texture_atlas, sprite_sheet_animations.add(anim));
 
@@ -216,6 +318,7 @@ impl SlimeBallBundle {
             },
             anim_handle: idle_animation.sprite_sheet_animation,
             play: benimator::Play,
-        })
+        });
+        Ok(())
     }
 }
@@ -0,0 +1,192 @@
+use crate::{error::AssetProblem, util};
+use benimator::SpriteSheetAnimation;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use bevy_ase::asset::{Animation, AseAsset, AseAssetMap, AseFileMap};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Every sprite sheet the game loads, from `assets/sprites.manifest.ron`. Available as a resource
+/// once loading finished.
+#[derive(Clone, Debug, Deserialize, TypeUuid)]
+#[uuid = "6b1f2a0e-5c7d-4f7a-9d8e-2f4b6c1a9e35"]
+pub struct SpriteManifest {
+    pub sheets: Vec<SpriteSheet>,
+}
+
+/// An aseprite file and what the game reads from it.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SpriteSheet {
+    /// The name systems look the sheet up by.
+    pub name: String,
+    /// Path relative to `assets/`.
+    pub path: String,
+    /// Frames read with [`LoadedSheet::texture`].
+    #[serde(default)]
+    pub frames: Vec<usize>,
+    /// Animation tags read with [`LoadedSheet::animation`].
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// A [`SpriteSheet`] whose file has been loaded.
+pub struct LoadedSheet<'a> {
+    pub sheet: &'a SpriteSheet,
+    pub asset_map: &'a AseAssetMap,
+}
+
+pub struct SpriteManifestHandle(pub Handle<SpriteManifest>);
+
+pub struct SpriteSheetHandles(pub Vec<(String, Handle<AseAsset>)>);
+
+pub struct SpriteManifestPlugin;
+
+impl Plugin for SpriteManifestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<SpriteManifest>()
+            .init_asset_loader::<SpriteManifestLoader>();
+    }
+}
+
+impl SpriteManifest {
+    pub const PATH: &'static str = "sprites.manifest.ron";
+
+    pub fn load(mut commands: Commands, asset_server: Res<AssetServer>) {
+        commands.insert_resource(SpriteManifestHandle(asset_server.load(Self::PATH)));
+    }
+
+    /// Looks up the sheet called `name`, which must already be loaded.
+    pub fn sheet<'a>(
+        &'a self,
+        ase_file_map: &'a AseFileMap,
+        name: &str,
+    ) -> Result<LoadedSheet<'a>, AssetProblem> {
+        let sheet = self
+            .sheets
+            .iter()
+            .find(|sheet| sheet.name == name)
+            .ok_or_else(|| AssetProblem::Undeclared {
+                name: name.to_owned(),
+                item: String::from("sheet"),
+            })?;
+        let asset_map =
+            ase_file_map
+                .get(Path::new(&sheet.path))
+                .ok_or_else(|| AssetProblem::MissingFile {
+                    path: sheet.path.clone(),
+                })?;
+        Ok(LoadedSheet { sheet, asset_map })
+    }
+
+    /// Checks every sheet, so all problems can be reported at once.
+    pub fn validate(
+        &self,
+        ase_file_map: &AseFileMap,
+        animations: &Assets<Animation>,
+    ) -> Vec<AssetProblem> {
+        let mut problems = Vec::new();
+        for sheet in &self.sheets {
+            let loaded = match self.sheet(ase_file_map, &sheet.name) {
+                Ok(loaded) => loaded,
+                Err(problem) => {
+                    problems.push(problem);
+                    continue;
+                }
+            };
+            for &frame in &sheet.frames {
+                if let Err(problem) = loaded.texture(frame) {
+                    problems.push(problem);
+                }
+            }
+            for tag in &sheet.tags {
+                if let Err(problem) = loaded.animation(animations, tag) {
+                    problems.push(problem);
+                }
+            }
+        }
+        problems
+    }
+
+    /// Lists every problem instead of stopping at the first.
+    fn check(&self) -> anyhow::Result<()> {
+        let mut problems = Vec::new();
+        for (i, sheet) in self.sheets.iter().enumerate() {
+            if self.sheets[..i]
+                .iter()
+                .any(|other| other.name == sheet.name)
+            {
+                problems.push(format!("sheet `{}` is declared twice", sheet.name));
+            }
+            if !sheet.path.ends_with(".aseprite") {
+                problems.push(format!(
+                    "sheet `{}` must be an .aseprite file, got {}",
+                    sheet.name, sheet.path
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("invalid sprite manifest:\n  {}", problems.join("\n  "))
+        }
+    }
+}
+
+impl<'a> LoadedSheet<'a> {
+    pub fn texture(&self, frame: usize) -> Result<Handle<Image>, AssetProblem> {
+        if !self.sheet.frames.contains(&frame) {
+            return Err(AssetProblem::Undeclared {
+                name: self.sheet.name.clone(),
+                item: format!("frame {}", frame),
+            });
+        }
+        self.asset_map
+            .texture(frame)
+            .cloned()
+            .ok_or_else(|| AssetProblem::MissingFrame {
+                path: self.sheet.path.clone(),
+                frame,
+            })
+    }
+
+    pub fn animation(
+        &self,
+        animations: &Assets<Animation>,
+        tag: &str,
+    ) -> Result<(Handle<TextureAtlas>, SpriteSheetAnimation), AssetProblem> {
+        if !self.sheet.tags.iter().any(|t| t == tag) {
+            return Err(AssetProblem::Undeclared {
+                name: self.sheet.name.clone(),
+                item: format!("tag \"{}\"", tag),
+            });
+        }
+        util::Animation::get_components(animations, self.asset_map, &self.sheet.path, tag)
+    }
+}
+
+#[derive(Default)]
+pub struct SpriteManifestLoader;
+
+impl AssetLoader for SpriteManifestLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let manifest: SpriteManifest = ron::de::from_bytes(bytes)?;
+            manifest.check()?;
+            load_context.set_default_asset(LoadedAsset::new(manifest));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["manifest.ron"]
+    }
+}
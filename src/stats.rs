@@ -0,0 +1,116 @@
+use crate::{game::Phase, AppState, ReplayMode, Score};
+use bevy::{app::AppExit, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+/// Progress kept across runs in `stats.ron` in the user's data directory.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub games_played: u32,
+    /// Times the memory overflowed in story mode.
+    pub story_completed: u32,
+    pub highest_score: u32,
+    /// The player broke out of the simulation through the egg overlay.
+    pub secret_found: bool,
+}
+
+/// Loads [`Stats`] at startup and keeps them up to date.
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Stats::load())
+            .add_system_set(
+                SystemSet::on_enter(AppState::Ready)
+                    .label("ready_enter")
+                    .with_system(Stats::on_game_start),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Ready)
+                    .label("ready_update")
+                    .with_system(Stats::on_phase.after("Game::update"))
+                    .with_system(Stats::on_score),
+            )
+            .add_system_to_stage(CoreStage::Last, Stats::on_exit);
+    }
+}
+
+impl Stats {
+    /// `None` where there is no data directory, e.g. on the web.
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("ad_opt").join("stats.ron"))
+    }
+
+    /// Falls back to empty stats if the file is missing or invalid.
+    pub fn load() -> Self {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return Stats::default(),
+        };
+        match fs::read_to_string(&path) {
+            Ok(stats) => ron::from_str(&stats).unwrap_or_else(|err| {
+                warn!("Ignoring invalid {}: {}", path.display(), err);
+                Stats::default()
+            }),
+            Err(_) => Stats::default(),
+        }
+    }
+
+    pub fn on_game_start(mut stats: ResMut<Stats>, replay: Res<ReplayMode>) {
+        stats.games_played += 1;
+        stats.save(&replay);
+    }
+
+    pub fn on_phase(
+        mut stats: ResMut<Stats>,
+        mut ev_phase: EventReader<Phase>,
+        replay: Res<ReplayMode>,
+    ) {
+        for ev in ev_phase.iter() {
+            if let Phase::TransEnd = ev {
+                stats.story_completed += 1;
+                stats.save(&replay);
+            }
+        }
+    }
+
+    pub fn on_score(mut stats: ResMut<Stats>, q_score: Query<&Score, Changed<Score>>) {
+        for score in q_score.iter() {
+            stats.highest_score = stats.highest_score.max(score.0);
+        }
+    }
+
+    pub fn on_exit(
+        stats: Res<Stats>,
+        mut ev_app_exit: EventReader<AppExit>,
+        replay: Res<ReplayMode>,
+    ) {
+        if ev_app_exit.iter().next().is_some() {
+            stats.save(&replay);
+        }
+    }
+
+    /// Writes the stats unless a replay is playing, which only repeats an earlier run.
+    pub fn save(&self, replay: &ReplayMode) {
+        if replay.is_playback() {
+            return;
+        }
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return,
+        };
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|stats| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+                }
+                fs::write(&path, stats).map_err(|err| err.to_string())
+            });
+        match result {
+            Ok(()) => info!("Saved stats to {}", path.display()),
+            Err(err) => warn!("Could not save stats to {}: {}", path.display(), err),
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    game::Wall, monster, settings::SlimeWarnings, slime_ball::NextShot, util, AppState, Monster,
+    Settings, SlimeBall, SpawnSlimeBall, Tuning, HALF_HEIGHT,
+};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+/// Warns about incoming slime: a marker on the top edge where the monster's next shot enters
+/// and, with `Slime warnings: Paths` in the settings, where every slime ball bounces next.
+pub struct TelegraphPlugin;
+
+impl Plugin for TelegraphPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::Restart)
+                .with_system(util::despawn_all::<EntryMarker>)
+                .with_system(util::despawn_all::<SlimePathDot>),
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::Ready)
+                .label("ready_enter")
+                .with_system(EntryMarker::spawn),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Ready)
+                .label("ready_update")
+                .with_system(EntryMarker::update.after("SpawnSlimeBall::handle_event"))
+                .with_system(SlimePathDot::update.after("SpawnSlimeBall::handle_event")),
+        );
+    }
+}
+
+/// Shown while the monster shoots, where its shot will come in.
+#[derive(Component)]
+pub struct EntryMarker;
+
+/// A dot of a predicted slime ball path. Spawned as more are needed, hidden when unused.
+#[derive(Component)]
+pub struct SlimePathDot;
+
+impl EntryMarker {
+    const SIZE: f32 = 6.;
+    const COLOR: Color = Color::rgb(0.9, 0.2, 0.2);
+    /// Blinks per second.
+    const BLINK_RATE: f32 = 4.;
+    /// Over the playfield, under the menus.
+    const Z: f32 = 8.;
+
+    pub fn spawn(mut commands: Commands) {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Self::COLOR,
+                    custom_size: Some(Vec2::splat(Self::SIZE)),
+                    ..Default::default()
+                },
+                // A diamond whose lower half pokes out of the top edge.
+                transform: Transform::from_xyz(0., HALF_HEIGHT, Self::Z)
+                    .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+                visibility: Visibility { is_visible: false },
+                ..Default::default()
+            })
+            .insert(EntryMarker);
+    }
+
+    pub fn update(
+        mut q_marker: Query<(&mut Sprite, &mut Transform, &mut Visibility), With<EntryMarker>>,
+        q_monster: Query<&Monster>,
+        next_shot: Res<NextShot>,
+        settings: Res<Settings>,
+        tuning: Res<Tuning>,
+        time: Res<Time>,
+    ) {
+        let shooting = q_monster.iter().any(|monster| {
+            matches!(
+                monster.phase,
+                monster::Phase::TransShoot | monster::Phase::Shoot
+            )
+        });
+        for (mut sprite, mut transform, mut visibility) in q_marker.iter_mut() {
+            let velocity = match next_shot.0 {
+                Some(velocity) if shooting && settings.slime_warnings != SlimeWarnings::Off => {
+                    velocity
+                }
+                _ => {
+                    visibility.is_visible = false;
+                    continue;
+                }
+            };
+            visibility.is_visible = true;
+            let x = SpawnSlimeBall::entry_x(velocity, &tuning) * tuning.physics_scale;
+            if transform.translation.x != x {
+                transform.translation.x = x;
+            }
+            let blink =
+                (time.seconds_since_startup() as f32 * Self::BLINK_RATE * std::f32::consts::TAU)
+                    .sin();
+            sprite.color.set_a(0.6 + 0.4 * blink);
+        }
+    }
+}
+
+impl SlimePathDot {
+    const STEPS: usize = 12;
+    /// Seconds of flight between two dots.
+    const STEP_SECS: f32 = 0.05;
+    const SIZE: f32 = 1.5;
+    const COLOR: Color = Color::rgba(0.45, 0.85, 0.25, 0.5);
+    /// Behind the slime balls.
+    const Z: f32 = 1.5;
+
+    /// Steps each slime ball under gravity and shape casts its ball against the walls it collides
+    /// with, bouncing off them with restitution 1. Other slime balls and the player are not
+    /// predicted.
+    pub fn update(
+        mut commands: Commands,
+        mut q_dot: Query<(&mut Transform, &mut Visibility), With<SlimePathDot>>,
+        q_slime_ball: Query<
+            (
+                &RigidBodyPositionComponent,
+                &RigidBodyVelocityComponent,
+                &ColliderFlagsComponent,
+            ),
+            With<SlimeBall>,
+        >,
+        q_collider: QueryPipelineColliderComponentsQuery,
+        q_wall: Query<(), With<Wall>>,
+        query_pipeline: Res<QueryPipeline>,
+        settings: Res<Settings>,
+        tuning: Res<Tuning>,
+    ) {
+        let mut dots = Vec::new();
+        if settings.slime_warnings == SlimeWarnings::Paths {
+            let collider_set = QueryPipelineColliderComponentsSet(&q_collider);
+            let is_wall = |handle: ColliderHandle| q_wall.get(handle.entity()).is_ok();
+            let ball = Ball::new(tuning.slime_ball.radius);
+            let gravity = Vec2::new(0., tuning.gravity);
+            let dt = Self::STEP_SECS;
+            for (position, velocity, flags) in q_slime_ball.iter() {
+                let translation = position.position.translation;
+                let mut position = Vec2::new(translation.x, translation.y);
+                let mut linvel = Vec2::new(velocity.linvel.x, velocity.linvel.y);
+                for _ in 0..Self::STEPS {
+                    let hit = query_pipeline.cast_shape(
+                        &collider_set,
+                        &Isometry::translation(position.x, position.y),
+                        &Vector::new(linvel.x, linvel.y),
+                        &ball,
+                        dt,
+                        // Shot balls pass through the top wall until they are inside.
+                        flags.solver_groups,
+                        Some(&is_wall),
+                    );
+                    // The wall's normal in world space, pointing back toward the ball.
+                    let hit =
+                        hit.map(|(_, toi)| (toi.toi, Vec2::new(toi.normal1.x, toi.normal1.y)));
+                    match hit {
+                        // Ignores walls the ball already moves away from.
+                        Some((toi, normal)) if linvel.dot(normal) < 0. => {
+                            position += linvel * toi;
+                            linvel -= 2. * linvel.dot(normal) * normal;
+                        }
+                        _ => {
+                            position += linvel * dt + gravity * dt * dt / 2.;
+                            linvel += gravity * dt;
+                        }
+                    }
+                    dots.push(position * tuning.physics_scale);
+                }
+            }
+        }
+
+        let mut dots = dots.into_iter();
+        for (mut transform, mut visibility) in q_dot.iter_mut() {
+            match dots.next() {
+                Some(dot) => {
+                    visibility.is_visible = true;
+                    transform.translation = dot.extend(Self::Z);
+                }
+                None => visibility.is_visible = false,
+            }
+        }
+        for dot in dots {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: Self::COLOR,
+                        custom_size: Some(Vec2::splat(Self::SIZE)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(dot.extend(Self::Z)),
+                    ..Default::default()
+                })
+                .insert(SlimePathDot);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    aim::Aim, game::Wall, settings::JumpMode, util, AppState, FrameInput, Player, ReadyToJump,
+    Settings, Tuning,
+};
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+/// A dotted arc from the player along the path the next jump would take, until it hits a wall.
+/// Turned off with `Jump preview` in the settings.
+pub struct TrajectoryPlugin;
+
+impl Plugin for TrajectoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_enter(AppState::Restart).with_system(util::despawn_all::<TrajectoryDot>),
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::Ready)
+                .label("ready_enter")
+                .with_system(TrajectoryDot::spawn),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Ready)
+                .label("ready_update")
+                .with_system(TrajectoryDot::update.after("TargetStatus::changed")),
+        );
+    }
+}
+
+/// The `index`th dot of the arc.
+#[derive(Component)]
+pub struct TrajectoryDot {
+    index: usize,
+}
+
+impl TrajectoryDot {
+    const COUNT: usize = 24;
+    /// Seconds of flight between two dots.
+    const STEP_SECS: f32 = 0.05;
+    const SIZE: f32 = 2.;
+    /// Behind the player and in front of the slime droplets.
+    const Z: f32 = 4.5;
+
+    pub fn spawn(mut commands: Commands) {
+        for index in 0..Self::COUNT {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgba(1., 1., 1., 0.6),
+                        custom_size: Some(Vec2::splat(Self::SIZE)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(0., 0., Self::Z),
+                    visibility: Visibility { is_visible: false },
+                    ..Default::default()
+                })
+                .insert(TrajectoryDot { index });
+        }
+    }
+
+    /// Steps the arc with the player's velocity after the jump impulse, the same way
+    /// [`ReadyToJump::update`] applies it, under the tuning's gravity.
+    pub fn update(
+        mut q_dot: Query<(&TrajectoryDot, &mut Transform, &mut Visibility)>,
+        q_player: Query<
+            (
+                &RigidBodyVelocityComponent,
+                &RigidBodyMassPropsComponent,
+                &Transform,
+            ),
+            (With<Player>, With<ReadyToJump>, Without<TrajectoryDot>),
+        >,
+        q_collider: QueryPipelineColliderComponentsQuery,
+        q_wall: Query<(), With<Wall>>,
+        query_pipeline: Res<QueryPipeline>,
+        aim: Res<Aim>,
+        input: Res<FrameInput>,
+        settings: Res<Settings>,
+        tuning: Res<Tuning>,
+    ) {
+        let mut dots = Vec::with_capacity(Self::COUNT);
+        if let (true, Ok((velocity, mass_props, transform))) =
+            (settings.trajectory_preview, q_player.get_single())
+        {
+            let charge = match settings.jump_mode {
+                JumpMode::Distance => None,
+                JumpMode::Charge => Some(input.charge.unwrap_or(0.)),
+            };
+            let impulse =
+                ReadyToJump::impulse(aim.position - transform.translation.xy(), charge, &tuning);
+            let mut velocity = **velocity;
+            velocity.apply_impulse(mass_props, impulse.into());
+
+            let collider_set = QueryPipelineColliderComponentsSet(&q_collider);
+            let is_wall = |handle: ColliderHandle| q_wall.get(handle.entity()).is_ok();
+            let gravity = Vec2::new(0., tuning.gravity);
+            let dt = Self::STEP_SECS;
+            let mut position = transform.translation.xy() / tuning.physics_scale;
+            let mut linvel = Vec2::new(velocity.linvel.x, velocity.linvel.y);
+            for _ in 0..Self::COUNT {
+                let step = linvel * dt + gravity * dt * dt / 2.;
+                let ray = Ray::new(
+                    Point::new(position.x, position.y),
+                    Vector::new(step.x, step.y),
+                );
+                if let Some((_, toi)) = query_pipeline.cast_ray(
+                    &collider_set,
+                    &ray,
+                    1.,
+                    true,
+                    InteractionGroups::all(),
+                    Some(&is_wall),
+                ) {
+                    dots.push(position + step * toi);
+                    break;
+                }
+                position += step;
+                linvel += gravity * dt;
+                dots.push(position);
+            }
+        }
+
+        for (dot, mut transform, mut visibility) in q_dot.iter_mut() {
+            match dots.get(dot.index) {
+                Some(position) => {
+                    visibility.is_visible = true;
+                    let translation = (*position * tuning.physics_scale).extend(Self::Z);
+                    if transform.translation != translation {
+                        transform.translation = translation;
+                    }
+                }
+                None => visibility.is_visible = false,
+            }
+        }
+    }
+}
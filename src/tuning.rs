@@ -0,0 +1,405 @@
+use crate::{util, BodyPart, Player, SlimeBall};
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+/// Gameplay constants, loaded from `assets/default.tuning.ron` (or `--tuning <path>` relative to
+/// `assets/`) while loading and then available as a resource.
+#[derive(Clone, Debug, Deserialize, TypeUuid)]
+#[uuid = "3d3951c5-1e98-484c-8f97-43a226689eff"]
+pub struct Tuning {
+    /// Pixels per physics unit.
+    pub physics_scale: f32,
+    /// Vertical gravity in physics units.
+    pub gravity: f32,
+    /// Jump impulse per pixel between the player and the cursor.
+    pub jump_impulse: f32,
+    /// Jumps in [`JumpMode::Charge`](crate::settings::JumpMode::Charge).
+    pub charge: ChargeTuning,
+    pub player: PlayerTuning,
+    pub slime_ball: SlimeBallTuning,
+    pub body_part: BodyPartTuning,
+    pub sound: SoundTuning,
+    pub particles: ParticlesTuning,
+    pub camera: CameraTuning,
+    pub gamepad: GamepadTuning,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PlayerTuning {
+    pub density: f32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SlimeBallTuning {
+    pub radius: f32,
+    pub density: f32,
+    pub restitution: f32,
+    /// Health of a shot slime ball is `base_health + health_per_score_squared * score²`.
+    pub base_health: i32,
+    pub health_per_score_squared: i32,
+    /// Health lost when hitting the player.
+    pub player_damage: i32,
+    /// Health lost when hitting anything else.
+    pub wall_damage: i32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BodyPartTuning {
+    pub density: f32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChargeTuning {
+    /// Seconds of holding the jump to charge it fully.
+    pub secs: f32,
+    /// Jump impulse of a released tap and of a full charge, growing linearly in between.
+    pub min_impulse: f32,
+    pub max_impulse: f32,
+}
+
+impl ChargeTuning {
+    /// From 0 to 1 after holding the jump for `secs`.
+    pub fn fraction(&self, secs: f32) -> f32 {
+        (secs / self.secs).min(1.)
+    }
+
+    pub fn impulse(&self, secs: f32) -> f32 {
+        self.min_impulse + (self.max_impulse - self.min_impulse) * self.fraction(secs)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SoundTuning {
+    /// Impact speed in physics units at which a collision sound plays at full volume.
+    pub full_volume_speed: f32,
+    /// Volume of the softest collision sound.
+    pub min_volume: f32,
+    /// Seconds for the music to fade from one track to the next.
+    pub crossfade_secs: f32,
+    /// The drums and bass layers join the main track once there are this many slime balls...
+    pub layer_slime_balls: [usize; 2],
+    /// ...or the score reaches this.
+    pub layer_scores: [u32; 2],
+}
+
+impl SoundTuning {
+    /// Volume of a collision sound, from `min_volume` to 1.
+    pub fn impact_volume(&self, speed: f32) -> f32 {
+        (speed / self.full_volume_speed).clamp(self.min_volume, 1.)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ParticlesTuning {
+    pub spray: ParticleTuning,
+    pub burst: ParticleTuning,
+    pub splat: ParticleTuning,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ParticleTuning {
+    /// Droplets per emission.
+    pub count: usize,
+    /// Longest lifetime of a droplet in seconds.
+    pub lifetime: f32,
+    /// Fastest droplet in pixels per second.
+    pub speed: f32,
+    /// Angle in radians the droplets spread over.
+    pub spread: f32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CameraTuning {
+    pub shake: ShakeTuning,
+    pub hit_stop: HitStopTuning,
+    pub zoom: ZoomTuning,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ShakeTuning {
+    pub enabled: bool,
+    /// Trauma, from 0 to 1, added per physics unit of speed the player is hit with.
+    pub trauma_per_speed: f32,
+    /// Trauma lost per second.
+    pub decay: f32,
+    /// Offset in pixels and rotation in radians at full trauma.
+    pub max_offset: f32,
+    pub max_angle: f32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct HitStopTuning {
+    pub enabled: bool,
+    /// Hits at least this fast stop the physics step.
+    pub min_speed: f32,
+    /// For this many frames.
+    pub frames: u32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ZoomTuning {
+    pub enabled: bool,
+    /// The view's size relative to the whole screen once zoomed in.
+    pub scale: f32,
+    /// Seconds to zoom in.
+    pub secs: f32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GamepadTuning {
+    /// Pixels per second the right stick moves the target at full tilt.
+    pub cursor_speed: f32,
+    /// Pixels from the player to the target at full tilt, when the stick picks the direction.
+    pub reach: f32,
+}
+
+pub struct TuningHandle {
+    pub path: String,
+    pub handle: Handle<Tuning>,
+}
+
+pub struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Tuning>()
+            .init_asset_loader::<TuningLoader>()
+            .add_system(Tuning::hot_reload);
+    }
+}
+
+impl Tuning {
+    pub const DEFAULT_PATH: &'static str = "default.tuning.ron";
+
+    pub fn load(mut commands: Commands, asset_server: Res<AssetServer>) {
+        let path = util::arg_value("tuning").unwrap_or_else(|| Self::DEFAULT_PATH.to_owned());
+        info!("Loading tuning from {}", path);
+        commands.insert_resource(TuningHandle {
+            handle: asset_server.load(path.as_str()),
+            path,
+        });
+        if let Err(err) = asset_server.watch_for_changes() {
+            warn!("Tuning changes will not be reloaded: {:?}", err);
+        }
+    }
+
+    /// Applies edits to the tuning file while the game is running. Invalid edits fail to load and
+    /// keep the previous values.
+    pub fn hot_reload(
+        mut ev_asset: EventReader<AssetEvent<Tuning>>,
+        tuning_handle: Option<Res<TuningHandle>>,
+        tunings: Res<Assets<Tuning>>,
+        tuning: Option<ResMut<Tuning>>,
+        mut rapier_configuration: ResMut<RapierConfiguration>,
+        mut q_body: Query<
+            (
+                &mut ColliderShapeComponent,
+                &mut ColliderMassPropsComponent,
+                &mut ColliderMaterialComponent,
+                &mut RigidBodyMassPropsComponent,
+                Option<&mut RigidBodyCcdComponent>,
+                &RigidBodyPositionComponent,
+                Option<&SlimeBall>,
+                Option<&BodyPart>,
+            ),
+            Or<(With<SlimeBall>, With<Player>, With<BodyPart>)>,
+        >,
+    ) {
+        let (tuning_handle, mut tuning) = match (tuning_handle, tuning) {
+            (Some(tuning_handle), Some(tuning)) => (tuning_handle, tuning),
+            _ => return,
+        };
+        let modified = ev_asset.iter().any(|ev| match ev {
+            AssetEvent::Modified { handle } => *handle == tuning_handle.handle,
+            _ => false,
+        });
+        let reloaded = match tunings.get(&tuning_handle.handle) {
+            Some(reloaded) if modified => reloaded,
+            _ => return,
+        };
+
+        info!("Reloading tuning");
+        let physics_scale = tuning.physics_scale;
+        *tuning = reloaded.clone();
+        if tuning.physics_scale != physics_scale {
+            warn!(
+                "Changing `physics_scale` needs a restart, keeping {}",
+                physics_scale
+            );
+            tuning.physics_scale = physics_scale;
+        }
+        rapier_configuration.gravity = Vector::new(0.0, tuning.gravity);
+
+        for (
+            mut collider_shape,
+            mut collider_mass_props,
+            mut collider_material,
+            mut rigid_body_mass_props,
+            rigid_body_ccd,
+            rigid_body_position,
+            slime_ball,
+            body_part,
+        ) in q_body.iter_mut()
+        {
+            let density = if slime_ball.is_some() {
+                let radius = tuning.slime_ball.radius;
+                *collider_shape = ColliderShape::ball(radius).into();
+                if let Some(mut rigid_body_ccd) = rigid_body_ccd {
+                    rigid_body_ccd.ccd_max_dist = radius;
+                }
+                collider_material.restitution = tuning.slime_ball.restitution;
+                tuning.slime_ball.density
+            } else if body_part.is_some() {
+                tuning.body_part.density
+            } else {
+                tuning.player.density
+            };
+            *collider_mass_props = ColliderMassProps::Density(density).into();
+            rigid_body_mass_props.local_mprops = collider_shape.mass_properties(density);
+            rigid_body_mass_props.update_world_mass_properties(&rigid_body_position.position);
+        }
+    }
+
+    /// Sets the physics scale and gravity once the tuning is loaded.
+    pub fn configure_physics(
+        tuning: Option<Res<Tuning>>,
+        mut rapier_configuration: ResMut<RapierConfiguration>,
+    ) {
+        if let Some(tuning) = tuning {
+            rapier_configuration.scale = tuning.physics_scale;
+            rapier_configuration.gravity = Vector::new(0.0, tuning.gravity);
+        }
+    }
+
+    pub fn slime_ball_health(&self, score: u32) -> i32 {
+        let score = score as i32;
+        self.slime_ball.base_health + self.slime_ball.health_per_score_squared * score * score
+    }
+
+    /// Lists every invalid value instead of stopping at the first.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut problems = Vec::new();
+        let mut positive = |name: &str, value: f32| {
+            if !(value.is_finite() && value > 0.) {
+                problems.push(format!("`{}` must be positive, got {}", name, value));
+            }
+        };
+        positive("physics_scale", self.physics_scale);
+        positive("player.density", self.player.density);
+        positive("slime_ball.radius", self.slime_ball.radius);
+        positive("slime_ball.density", self.slime_ball.density);
+        positive("body_part.density", self.body_part.density);
+        positive("charge.secs", self.charge.secs);
+        positive("sound.full_volume_speed", self.sound.full_volume_speed);
+        positive("sound.crossfade_secs", self.sound.crossfade_secs);
+        positive("camera.shake.decay", self.camera.shake.decay);
+        positive("gamepad.cursor_speed", self.gamepad.cursor_speed);
+        positive("gamepad.reach", self.gamepad.reach);
+        positive("camera.zoom.scale", self.camera.zoom.scale);
+        positive("camera.zoom.secs", self.camera.zoom.secs);
+        for (name, value) in [
+            (
+                "camera.shake.trauma_per_speed",
+                self.camera.shake.trauma_per_speed,
+            ),
+            ("camera.shake.max_offset", self.camera.shake.max_offset),
+            ("camera.shake.max_angle", self.camera.shake.max_angle),
+            ("camera.hit_stop.min_speed", self.camera.hit_stop.min_speed),
+            ("charge.min_impulse", self.charge.min_impulse),
+        ] {
+            if !(value.is_finite() && value >= 0.) {
+                problems.push(format!("`{}` must not be negative, got {}", name, value));
+            }
+        }
+        for (name, particle) in [
+            ("spray", &self.particles.spray),
+            ("burst", &self.particles.burst),
+            ("splat", &self.particles.splat),
+        ] {
+            positive(&format!("particles.{}.lifetime", name), particle.lifetime);
+            positive(&format!("particles.{}.speed", name), particle.speed);
+        }
+
+        if !self.gravity.is_finite() {
+            problems.push(format!("`gravity` must be finite, got {}", self.gravity));
+        }
+        if !(self.jump_impulse.is_finite() && self.jump_impulse >= 0.) {
+            problems.push(format!(
+                "`jump_impulse` must not be negative, got {}",
+                self.jump_impulse
+            ));
+        }
+        if !(self.charge.max_impulse.is_finite()
+            && self.charge.max_impulse >= self.charge.min_impulse)
+        {
+            problems.push(format!(
+                "`charge.max_impulse` must not be below `charge.min_impulse`, got {}",
+                self.charge.max_impulse
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.slime_ball.restitution) {
+            problems.push(format!(
+                "`slime_ball.restitution` must be between 0 and 1, got {}",
+                self.slime_ball.restitution
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.sound.min_volume) {
+            problems.push(format!(
+                "`sound.min_volume` must be between 0 and 1, got {}",
+                self.sound.min_volume
+            ));
+        }
+        if self.slime_ball.base_health <= 0 {
+            problems.push(format!(
+                "`slime_ball.base_health` must be positive, got {}",
+                self.slime_ball.base_health
+            ));
+        }
+        for (name, value) in [
+            (
+                "slime_ball.health_per_score_squared",
+                self.slime_ball.health_per_score_squared,
+            ),
+            ("slime_ball.player_damage", self.slime_ball.player_damage),
+            ("slime_ball.wall_damage", self.slime_ball.wall_damage),
+        ] {
+            if value < 0 {
+                problems.push(format!("`{}` must not be negative, got {}", name, value));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("invalid tuning:\n  {}", problems.join("\n  "))
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TuningLoader;
+
+impl AssetLoader for TuningLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let tuning: Tuning = ron::de::from_bytes(bytes)?;
+            tuning.validate()?;
+            load_context.set_default_asset(LoadedAsset::new(tuning));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tuning.ron"]
+    }
+}
@@ -1,5 +1,7 @@
+use crate::error::AssetProblem;
 use crate::nalgebra;
 use crate::nalgebra::Vector2;
+use crate::{HALF_HEIGHT, HALF_WIDTH};
 use benimator::SpriteSheetAnimation;
 use bevy::prelude::*;
 use bevy_ase::asset::AseAssetMap;
@@ -25,7 +27,11 @@ pub fn screen_to_world_pos(
         // reduce it to a 2D value
         let world_pos: Vec2 = world_pos.truncate();
 
-        world_pos
+        // aim at the edge of the playfield from the letterbox bars
+        world_pos.clamp(
+            Vec2::new(-HALF_WIDTH, -HALF_HEIGHT),
+            Vec2::new(HALF_WIDTH, HALF_HEIGHT),
+        )
     })
 }
 
@@ -44,13 +50,20 @@ impl Animation {
     pub fn get_components(
         animations: &Assets<bevy_ase::asset::Animation>,
         asset_map: &AseAssetMap,
+        path: &str,
         tag: &str,
-    ) -> (Handle<TextureAtlas>, SpriteSheetAnimation) {
-        let animation_handle = asset_map.animations(tag).unwrap().first().unwrap();
-        let animation = animations.get(animation_handle).unwrap();
+    ) -> Result<(Handle<TextureAtlas>, SpriteSheetAnimation), AssetProblem> {
+        let animation = asset_map
+            .animations(tag)
+            .and_then(|animations| animations.first())
+            .and_then(|animation_handle| animations.get(animation_handle))
+            .ok_or_else(|| AssetProblem::MissingTag {
+                path: path.to_owned(),
+                tag: tag.to_owned(),
+            })?;
         let texture_atlas = animation.atlas();
         let ssa = SpriteSheetAnimation::from(animation);
-        (texture_atlas, ssa)
+        Ok((texture_atlas, ssa))
     }
 
     pub fn from_components(
@@ -73,6 +86,12 @@ impl Animation {
     }
 }
 
+pub fn despawn_all<T: Component>(mut commands: Commands, q_entity: Query<Entity, With<T>>) {
+    for e in q_entity.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
 #[derive(Ord, PartialOrd, Eq, PartialEq)]
 pub struct DespawnEntity(pub Entity);
 
@@ -87,3 +106,11 @@ impl DespawnEntity {
         }
     }
 }
+
+/// Returns the value following `--{name}` on the command line, if any.
+pub fn arg_value(name: &str) -> Option<String> {
+    let flag = format!("--{}", name);
+    let mut args = std::env::args().skip_while(|arg| *arg != flag);
+    args.next();
+    args.next()
+}
@@ -0,0 +1,101 @@
+//! Helpers shared by the integration tests, which run the game with [`HeadlessPlugin`].
+#![allow(dead_code)]
+
+use bevy::{
+    input::{keyboard::KeyboardInput, ElementState},
+    prelude::*,
+};
+use bevy_jam_one::{
+    game::Phase, AppState, AssetProblems, Game, HeadlessPlugin, ReplayMode, RngSeed, Score,
+    Settings,
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Loading takes a while in debug builds.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A headless game with the default settings that neither reads nor writes the settings and
+/// stats of whoever runs the tests.
+pub fn app(seed: u64, replay: ReplayMode) -> App {
+    app_with_settings(Settings::default(), seed, replay)
+}
+
+/// Like [`app`], with `settings` instead of the defaults.
+pub fn app_with_settings(settings: Settings, seed: u64, replay: ReplayMode) -> App {
+    let dir = env!("CARGO_TARGET_TMPDIR");
+    std::env::set_var("XDG_CONFIG_HOME", dir);
+    std::env::set_var("XDG_DATA_HOME", dir);
+
+    let mut app = App::new();
+    app.insert_resource(settings)
+        .insert_resource(RngSeed(seed))
+        .insert_resource(replay)
+        .add_plugin(HeadlessPlugin);
+    app
+}
+
+pub fn state(app: &App) -> AppState {
+    app.world
+        .get_resource::<State<AppState>>()
+        .unwrap()
+        .current()
+        .clone()
+}
+
+pub fn phase(app: &App) -> Phase {
+    app.world.get_resource::<Game>().unwrap().phase().clone()
+}
+
+pub fn score(app: &mut App) -> u32 {
+    app.world.query::<&Score>().single(&app.world).0
+}
+
+/// Steps the app until `done`, panicking with `what` after [`TIMEOUT`].
+pub fn update_until(app: &mut App, what: &str, done: impl Fn(&App) -> bool) {
+    let start = Instant::now();
+    while !done(app) {
+        assert!(start.elapsed() < TIMEOUT, "timed out waiting for {}", what);
+        app.update();
+        // Assets load on other threads.
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Waits until the assets are loaded and the title menu is shown.
+pub fn load(app: &mut App) {
+    update_until(app, "the title menu", |app| state(app) != AppState::Loading);
+    if state(app) == AppState::Error {
+        let problems = app.world.get_resource::<AssetProblems>().unwrap();
+        panic!("the assets did not load: {:?}", problems.0);
+    }
+    assert_eq!(state(app), AppState::Menu);
+}
+
+/// Presses and releases `key` over two frames.
+pub fn press(app: &mut App, key: KeyCode) {
+    for state in [ElementState::Pressed, ElementState::Released] {
+        app.world
+            .get_resource_mut::<Events<KeyboardInput>>()
+            .unwrap()
+            .send(KeyboardInput {
+                scan_code: 0,
+                key_code: Some(key),
+                state,
+            });
+        app.update();
+    }
+}
+
+/// Picks story mode in the title menu, whose first item it is.
+pub fn start_story(app: &mut App) {
+    load(app);
+    // The menu ignores input on its first frame.
+    app.update();
+    press(app, KeyCode::Return);
+    update_until(app, "the round to start", |app| {
+        state(app) == AppState::Ready
+    });
+}
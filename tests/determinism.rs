@@ -0,0 +1,72 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_jam_one::{game::Phase, BodyPart, BodyPartTextures, ReplayMode, SlimeBall};
+use bevy_rapier2d::prelude::*;
+
+/// Everything that was spawned with random properties, in order.
+#[derive(Default)]
+struct Spawned(Vec<Spawn>);
+
+#[derive(Debug, PartialEq)]
+enum Spawn {
+    SlimeBall { velocity: [f32; 2] },
+    BodyPart { texture: usize, rotation: f32 },
+}
+
+fn record_spawns(
+    mut spawned: ResMut<Spawned>,
+    q_slime_ball: Query<&RigidBodyVelocityComponent, Added<SlimeBall>>,
+    q_body_part: Query<(&Handle<Image>, &RigidBodyPositionComponent), Added<BodyPart>>,
+    textures: Option<Res<BodyPartTextures>>,
+) {
+    for velocity in q_slime_ball.iter() {
+        spawned.0.push(Spawn::SlimeBall {
+            velocity: [velocity.linvel.x, velocity.linvel.y],
+        });
+    }
+    for (texture, position) in q_body_part.iter() {
+        let textures = &textures.as_ref().unwrap().0;
+        spawned.0.push(Spawn::BodyPart {
+            texture: textures.iter().position(|t| t == texture).unwrap(),
+            rotation: position.position.rotation.angle(),
+        });
+    }
+}
+
+/// Plays the intro, where the monster shoots once and the ball bounces until it breaks, with
+/// nothing but the game itself drawing from the seed.
+fn run(seed: u64) -> Vec<Spawn> {
+    let mut app = common::app(seed, ReplayMode::Off);
+    // As for replays, so the physics does not depend on how fast the test runs.
+    app.world
+        .get_resource_mut::<RapierConfiguration>()
+        .unwrap()
+        .timestep_mode = TimestepMode::FixedTimestep;
+    app.init_resource::<Spawned>().add_system(record_spawns);
+    common::start_story(&mut app);
+    app.update();
+
+    // Any key leaves the press start overlay and starts the intro.
+    common::press(&mut app, KeyCode::Space);
+    common::update_until(&mut app, "the intro to end", |app| {
+        common::phase(app) == Phase::Dead
+    });
+    app.world.remove_resource::<Spawned>().unwrap().0
+}
+
+#[test]
+fn same_seed_same_run() {
+    let spawned = run(42);
+    assert!(
+        spawned
+            .iter()
+            .any(|spawn| matches!(spawn, Spawn::SlimeBall { .. })),
+        "{:?}",
+        spawned
+    );
+    assert_eq!(run(42), spawned);
+
+    // The shot's velocity is drawn from the seed.
+    assert_ne!(run(43), spawned);
+}
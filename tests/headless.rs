@@ -0,0 +1,38 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_jam_one::{game::Phase, AppState, FrameInput, ReplayMode};
+
+#[test]
+fn story_mode_starts_from_the_title_menu() {
+    let mut app = common::app(1, ReplayMode::Off);
+    common::start_story(&mut app);
+    app.update();
+    assert_eq!(common::phase(&app), Phase::Start);
+
+    // Any key leaves the press start overlay and starts the intro.
+    common::press(&mut app, KeyCode::Space);
+    common::update_until(&mut app, "the intro", |app| {
+        common::phase(app) == Phase::Intro
+    });
+    for _ in 0..60 {
+        app.update();
+    }
+    assert_eq!(common::state(&app), AppState::Ready);
+}
+
+#[test]
+fn escape_on_the_egg_overlay_shows_the_credits() {
+    let mut app = common::app(1, ReplayMode::Off);
+    common::start_story(&mut app);
+    for key in FrameInput::SECRET {
+        common::press(&mut app, key);
+    }
+    assert_eq!(common::phase(&app), Phase::Egg);
+
+    // Escape is also any key, which leaves the overlay instead of pausing.
+    common::press(&mut app, KeyCode::Escape);
+    common::update_until(&mut app, "the credits", |app| {
+        common::state(app) == AppState::Credits
+    });
+}
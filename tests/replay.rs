@@ -0,0 +1,98 @@
+mod common;
+
+use bevy::{app::AppExit, prelude::*};
+use bevy_jam_one::{
+    frame_input::SimulationFrame, game::Phase, replay::ReplayFile, Aim, Player, ReplayMode,
+};
+use std::{fs, path::Path};
+
+/// Score, phase and where the player ended up.
+fn outcome(app: &mut App) -> (u32, Phase, Vec3) {
+    let player = app
+        .world
+        .query_filtered::<&Transform, With<Player>>()
+        .single(&app.world)
+        .translation;
+    (common::score(app), common::phase(app), player)
+}
+
+fn play_back(replay: ReplayFile) -> (u32, Phase, Vec3) {
+    let mut app = common::app(
+        0,
+        ReplayMode::Playback {
+            replay,
+            next_input: 0,
+            next_shot: 0,
+            finished: false,
+        },
+    );
+    common::load(&mut app);
+    common::update_until(&mut app, "the replay to finish", |app| {
+        !app.world
+            .get_resource::<ReplayMode>()
+            .unwrap()
+            .is_playback()
+    });
+    outcome(&mut app)
+}
+
+#[test]
+fn playback_ends_like_the_recording() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("playback_ends_like_the_recording.ron");
+    let mut app = common::app(
+        7,
+        ReplayMode::Record {
+            path: path.clone(),
+            replay: ReplayFile::default(),
+        },
+    );
+    common::start_story(&mut app);
+    app.update();
+    // Leaves the press start overlay, sits through the intro and starts the main round.
+    common::press(&mut app, KeyCode::Space);
+    common::update_until(&mut app, "the intro to end", |app| {
+        common::phase(app) == Phase::Dead
+    });
+    common::press(&mut app, KeyCode::Space);
+    common::update_until(&mut app, "the main round", |app| {
+        common::phase(app) == Phase::Main
+    });
+    let main_frame = app.world.get_resource::<SimulationFrame>().unwrap().0;
+    // Off to the side, so the jumps carry the player away from where it stands. There is no
+    // cursor to move the aim when running headless.
+    app.world.get_resource_mut::<Aim>().unwrap().position = Vec2::new(100., 0.);
+    // Jumps toward the aim every half second.
+    for frame in 0..600 {
+        if frame % 30 == 0 {
+            common::press(&mut app, KeyCode::Space);
+        } else {
+            app.update();
+        }
+    }
+    app.world
+        .get_resource_mut::<Events<AppExit>>()
+        .unwrap()
+        .send(AppExit);
+    app.update();
+    let recorded = outcome(&mut app);
+    drop(app);
+    // The intro's shot hits the player.
+    assert!(recorded.0 > 0, "{:?}", recorded);
+
+    let replay: ReplayFile = ron::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(replay
+        .inputs
+        .iter()
+        .any(|(frame, input)| *frame > main_frame && input.click.is_some()));
+    assert_eq!(
+        (replay.score, replay.phase.clone().unwrap()),
+        (recorded.0, recorded.1.clone())
+    );
+
+    // Without the jumps of the main round, the player ends up somewhere else.
+    let mut perturbed: ReplayFile = ron::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    perturbed.inputs.retain(|(frame, _)| *frame <= main_frame);
+    assert_ne!(play_back(perturbed).2, recorded.2);
+
+    assert_eq!(play_back(replay), recorded);
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_jam_one::{
+    settings::SlimeWarnings, telegraph::SlimePathDot, ReplayMode, Settings, SlimeBall,
+    SpawnSlimeBall, Tuning, HALF_WIDTH,
+};
+use bevy_rapier2d::prelude::*;
+
+#[test]
+fn slime_paths_bounce_off_walls() {
+    let settings = Settings {
+        slime_warnings: SlimeWarnings::Paths,
+        ..Default::default()
+    };
+    let mut app = common::app_with_settings(settings, 1, ReplayMode::Off);
+    common::start_story(&mut app);
+
+    // Close to the right wall and moving toward it, in physics units.
+    let start = 20.;
+    app.world
+        .get_resource_mut::<Events<SpawnSlimeBall>>()
+        .unwrap()
+        .send(SpawnSlimeBall {
+            position: Some(Isometry::translation(start, 0.)),
+            velocity: Some(Vector::new(10., 0.)),
+            health: 1,
+        });
+    app.update();
+    // As if it had already come in through the top wall, which shot balls pass through.
+    for mut flags in app
+        .world
+        .query_filtered::<&mut ColliderFlagsComponent, With<SlimeBall>>()
+        .iter_mut(&mut app.world)
+    {
+        flags.solver_groups = InteractionGroups::new(1 << 2, !0);
+    }
+    for _ in 0..2 {
+        app.update();
+    }
+
+    let dots: Vec<_> = app
+        .world
+        .query_filtered::<(&Transform, &Visibility), With<SlimePathDot>>()
+        .iter(&app.world)
+        .filter(|(_, visibility)| visibility.is_visible)
+        .map(|(transform, _)| transform.translation.x)
+        .collect();
+    assert!(!dots.is_empty());
+    assert!(dots.iter().all(|&x| x <= HALF_WIDTH), "{:?}", dots);
+    // Back on the near side of where it started after bouncing.
+    let scale = app.world.get_resource::<Tuning>().unwrap().physics_scale;
+    assert!(dots.iter().any(|&x| x < start * scale), "{:?}", dots);
+}